@@ -1,11 +1,11 @@
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{criterion_group, criterion_main, Criterion};
 use rand::Rng;
 use rust_kzg_bn254::{blob::Blob, kzg::Kzg};
 use std::time::Duration;
 
 fn bench_kzg_commit(c: &mut Criterion) {
     let mut rng = rand::thread_rng();
-    let mut kzg = Kzg::setup(false).unwrap();
+    let mut kzg = Kzg::setup(true).unwrap();
 
     c.bench_function("bench_kzg_commit_10000", |b| {
         let random_blob: Vec<u8> = (0..10000).map(|_| rng.gen_range(32..=126) as u8).collect();
@@ -33,6 +33,15 @@ fn bench_kzg_commit(c: &mut Criterion) {
             .unwrap();
         b.iter(|| kzg.commit(&input_poly).unwrap());
     });
+
+    c.bench_function("bench_kzg_commit_precomputed_50000", |b| {
+        let random_blob: Vec<u8> = (0..50000).map(|_| rng.gen_range(32..=126) as u8).collect();
+        let input = Blob::from_bytes_and_pad(&random_blob);
+        let input_poly = input.to_polynomial().unwrap();
+        kzg.precompute_msm_tables(&[input_poly.len()], 1 << 30)
+            .unwrap();
+        b.iter(|| kzg.commit(&input_poly).unwrap());
+    });
 }
 
 fn criterion_config() -> Criterion {
@@ -0,0 +1,44 @@
+use ark_bn254::Fr;
+use ark_std::UniformRand;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use rust_kzg_bn254::{kzg::Kzg, polynomial::Polynomial};
+use std::time::Duration;
+
+fn bench_kzg_proof(c: &mut Criterion) {
+    let mut rng = rand::thread_rng();
+    let log_sizes = [12, 14, 16, 18, 20];
+    let sizes: Vec<usize> = log_sizes
+        .iter()
+        .map(|log_size| 1usize << log_size)
+        .collect();
+    // a group IFFT at 2^20 takes minutes, the insecure srs derives its bases from tau
+    let kzg = Kzg::setup_insecure(Fr::rand(&mut rng), 1 << 20, &sizes).unwrap();
+    let mut group = c.benchmark_group("bench_kzg_proof");
+
+    for size in sizes {
+        let elements: Vec<Fr> = (0..size).map(|_| Fr::rand(&mut rng)).collect();
+        let polynomial = Polynomial::new(&elements, size * 32).unwrap();
+
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, &size| {
+            b.iter(|| {
+                kzg.compute_kzg_proof_with_roots_of_unity(&polynomial, (size / 3) as u64)
+                    .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn criterion_config() -> Criterion {
+    Criterion::default()
+        .warm_up_time(Duration::from_secs(5)) // Warm-up time
+        .measurement_time(Duration::from_secs(10)) // Measurement time
+        .sample_size(10) // Number of samples to take
+}
+
+criterion_group!(
+    name = benches;
+    config = criterion_config();
+    targets = bench_kzg_proof
+);
+criterion_main!(benches);
@@ -6,7 +6,16 @@ fn bench_kzg_setup(c: &mut Criterion) {
     c.bench_function("bench_kzg_setup", |b| {
         b.iter(|| Kzg::setup(true).unwrap());
 
-        b.iter(|| Kzg::setup(false).unwrap());
+        b.iter(|| {
+            Kzg::setup_from_files(
+                "src/test-files/g1.point",
+                "src/test-files/g2.point",
+                "",
+                3000,
+                3000,
+            )
+            .unwrap()
+        });
     });
 }
 
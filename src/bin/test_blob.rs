@@ -7,7 +7,7 @@ use rust_kzg_bn254::kzg::Kzg;
 // y: Ez88I+rPb1gYjuepHJFaW9DtXIXzZKy0eEVFwKbwEtA=
 
 pub fn main() {
-    let mut kzg = Kzg::setup(true).unwrap();
+    let kzg = Kzg::setup(true).unwrap();
     let mut blob = Blob::new(Vec::from("hello"));
     blob.pad_data().unwrap();
     let commitment = kzg.blob_to_kzg_commitment(&blob).unwrap();
@@ -1,4 +1,12 @@
-use crate::{errors::BlobError, helpers, polynomial::Polynomial};
+use crate::{
+    consts::BYTES_PER_FIELD_ELEMENT,
+    encoding::{self, EncodingParams},
+    errors::{BlobError, KzgError},
+    helpers,
+    polynomial::{Polynomial, PolynomialForm},
+};
+use ark_bn254::Fr;
+use ark_std::Zero;
 
 /// A blob which is Eigen DA spec aligned.
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -20,7 +28,7 @@ impl Blob {
 
     /// Creates a new `Blob` from the given data.
     pub fn is_padded(&self) -> bool {
-        return self.is_padded;
+        self.is_padded
     }
 
     /// Creates a new `Blob` from the provided byte slice and pads it according to DA specs.
@@ -30,10 +38,48 @@ impl Blob {
         Blob {
             blob_data: padded_input,
             is_padded: true,
-            length_after_padding: length_after_padding,
+            length_after_padding,
         }
     }
 
+    /// Creates a new `Blob` from data that is already padded, such as a blob read
+    /// back from EigenDA or produced by a `codec::PayloadCodec`.
+    pub fn from_padded_bytes(blob_data: &[u8]) -> Self {
+        Blob {
+            blob_data: blob_data.to_vec(),
+            is_padded: true,
+            length_after_padding: blob_data.len(),
+        }
+    }
+
+    /// Rebuilds the padded blob of `blob_length` bytes from enough of the chunks
+    /// `encoding::encode_chunks` made of its polynomial in the given form, see
+    /// `encoding::recover_polynomial`. `remove_padding` then gives back the
+    /// original bytes.
+    pub fn from_chunks(
+        indices: &[u64],
+        chunks: &[Vec<Fr>],
+        params: &EncodingParams,
+        blob_length: usize,
+        form: PolynomialForm,
+    ) -> Result<Self, KzgError> {
+        let num_elements = blob_length.div_ceil(BYTES_PER_FIELD_ELEMENT);
+        let polynomial = encoding::recover_polynomial(indices, chunks, params, num_elements)?;
+        let elements = match form {
+            PolynomialForm::Eval => polynomial.to_eval_form()?.to_vec(),
+            PolynomialForm::Coeff => polynomial.to_vec(),
+        };
+        // the elements past the blob are the zeros it was padded with
+        if elements[num_elements..]
+            .iter()
+            .any(|element| !element.is_zero())
+        {
+            return Err(KzgError::InconsistentChunks);
+        }
+        let blob_data = helpers::to_byte_array(&elements, blob_length);
+        Ok(Self::from_padded_bytes(&blob_data))
+    }
+
     /// Returns the blob data
     pub fn get_blob_data(&self) -> Vec<u8> {
         self.blob_data.clone()
@@ -44,6 +90,11 @@ impl Blob {
         self.blob_data.len()
     }
 
+    /// Checks if the blob holds no data.
+    pub fn is_empty(&self) -> bool {
+        self.blob_data.is_empty()
+    }
+
     /// Pads the blob data in-place if it is not already padded.
     pub fn pad_data(&mut self) -> Result<(), BlobError> {
         if self.is_padded {
@@ -68,15 +119,23 @@ impl Blob {
         }
     }
 
-    /// Converts the blob data to a `Polynomial` if the data is padded.
+    /// Converts the blob data to a `Polynomial` in evaluation form if the data is padded.
     pub fn to_polynomial(&self) -> Result<Polynomial, BlobError> {
+        self.to_polynomial_with_form(PolynomialForm::Eval)
+    }
+
+    /// Converts the blob data to a `Polynomial` whose elements are read in the
+    /// given form, the one the disperser was told to use.
+    pub fn to_polynomial_with_form(&self, form: PolynomialForm) -> Result<Polynomial, BlobError> {
         if !self.is_padded {
             Err(BlobError::NotPaddedError)
         } else {
             let fr_vec = helpers::to_fr_array(&self.blob_data);
-            let poly = Polynomial::new(&fr_vec, self.length_after_padding)
-                .map_err(|err| BlobError::GenericError(err.to_string()))?;
-            Ok(poly)
+            Ok(Polynomial::new_with_form(
+                &fr_vec,
+                self.length_after_padding,
+                form,
+            )?)
         }
     }
 }
@@ -214,6 +273,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_from_chunks() {
+        use crate::consts::GETTYSBURG_ADDRESS_BYTES;
+
+        // 1 of 4 chunks is enough at a coding ratio of 4
+        let params = EncodingParams::new(64, 4).unwrap();
+        for form in [PolynomialForm::Eval, PolynomialForm::Coeff] {
+            let blob = Blob::from_bytes_and_pad(GETTYSBURG_ADDRESS_BYTES);
+            let polynomial = blob.to_polynomial_with_form(form).unwrap();
+            let chunks = encoding::encode_chunks(&polynomial, &params).unwrap();
+
+            for index in 0..4 {
+                let mut recovered = Blob::from_chunks(
+                    &[index],
+                    &chunks[index as usize..=index as usize],
+                    &params,
+                    blob.len(),
+                    form,
+                )
+                .unwrap();
+                assert_eq!(recovered, blob);
+                recovered.remove_padding().unwrap();
+                assert_eq!(recovered.get_blob_data(), GETTYSBURG_ADDRESS_BYTES);
+            }
+        }
+
+        // chunks of a longer polynomial than the blob
+        let blob = Blob::from_bytes_and_pad(GETTYSBURG_ADDRESS_BYTES);
+        let chunks = encoding::encode_chunks(&blob.to_polynomial().unwrap(), &params).unwrap();
+        assert_eq!(
+            Blob::from_chunks(&[0, 1], &chunks[..2], &params, 1000, PolynomialForm::Eval),
+            Err(KzgError::InconsistentChunks)
+        );
+    }
+
     #[test]
     fn test_new_blob_creation() {
         use crate::consts::GETTYSBURG_ADDRESS_BYTES;
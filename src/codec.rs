@@ -0,0 +1,190 @@
+//! Encodings of rollup payloads into blobs.
+//!
+//! A payload is arbitrary bytes. It can't be dispersed as is: every 32 bytes of a
+//! blob must be a field element, and padding to field elements loses the exact
+//! length of the payload. A codec frames the payload with a header that records
+//! its version and length, so decoding returns exactly the bytes that were encoded.
+
+use crate::blob::Blob;
+use crate::consts::BYTES_PER_FIELD_ELEMENT;
+use crate::errors::BlobError;
+use crate::helpers;
+
+/// Turns payloads into blobs and back.
+pub trait PayloadCodec {
+    /// the version byte the codec writes in the header
+    fn version(&self) -> u8;
+
+    /// a padded blob holding the payload
+    fn encode(&self, payload: &[u8]) -> Result<Blob, BlobError>;
+
+    /// the payload held by a blob, rejecting any blob this codec wouldn't produce
+    /// beyond trailing zero bytes
+    fn decode(&self, blob: &Blob) -> Result<Vec<u8>, BlobError>;
+}
+
+/// The encoding of version 0 of the EigenDA payloads, `DefaultBlobCodec` in the
+/// go client.
+///
+/// The blob starts with a header of one field element: a zero byte, the version
+/// byte, the payload length as a big-endian u32 and zero bytes. The payload
+/// follows, padded with a zero byte every 31 bytes.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct EigenDaV0Codec;
+
+impl EigenDaV0Codec {
+    const VERSION: u8 = 0;
+    const HEADER_SIZE: usize = BYTES_PER_FIELD_ELEMENT;
+}
+
+impl PayloadCodec for EigenDaV0Codec {
+    fn version(&self) -> u8 {
+        Self::VERSION
+    }
+
+    fn encode(&self, payload: &[u8]) -> Result<Blob, BlobError> {
+        let length =
+            u32::try_from(payload.len()).map_err(|_| BlobError::PayloadTooLong(payload.len()))?;
+
+        let body = helpers::convert_by_padding_empty_byte(payload);
+        let mut data = Vec::with_capacity(Self::HEADER_SIZE + body.len());
+        data.extend([0, Self::VERSION]);
+        data.extend(length.to_be_bytes());
+        data.resize(Self::HEADER_SIZE, 0);
+        data.extend(body);
+        Ok(Blob::from_padded_bytes(&data))
+    }
+
+    fn decode(&self, blob: &Blob) -> Result<Vec<u8>, BlobError> {
+        if !blob.is_padded() {
+            return Err(BlobError::NotPaddedError);
+        }
+        let data = blob.get_blob_data();
+        if data.len() < Self::HEADER_SIZE {
+            return Err(BlobError::MissingHeader);
+        }
+        let (header, body) = data.split_at(Self::HEADER_SIZE);
+        if header[0] != 0 || header[6..].iter().any(|byte| *byte != 0) {
+            return Err(BlobError::InvalidHeader);
+        }
+        if header[1] != Self::VERSION {
+            return Err(BlobError::UnsupportedVersion(header[1]));
+        }
+        let mut length_bytes = [0u8; 4];
+        length_bytes.copy_from_slice(&header[2..6]);
+        let length = u32::from_be_bytes(length_bytes) as usize;
+
+        // every field element of the body starts with a zero byte
+        if let Some(i) = (0..body.len())
+            .step_by(BYTES_PER_FIELD_ELEMENT)
+            .find(|&i| body[i] != 0)
+        {
+            return Err(BlobError::NonZeroPadding(Self::HEADER_SIZE + i));
+        }
+        let mut payload = helpers::remove_empty_byte_from_padded_bytes(body);
+        if payload.len() < length {
+            return Err(BlobError::PayloadTruncated {
+                expected: length,
+                actual: payload.len(),
+            });
+        }
+        // whatever follows the payload can only be the zeros padding the blob
+        if let Some(i) = payload[length..].iter().position(|byte| *byte != 0) {
+            let unpadded_offset = length + i;
+            let offset = Self::HEADER_SIZE
+                + unpadded_offset / (BYTES_PER_FIELD_ELEMENT - 1) * BYTES_PER_FIELD_ELEMENT
+                + unpadded_offset % (BYTES_PER_FIELD_ELEMENT - 1)
+                + 1;
+            return Err(BlobError::NonZeroPadding(offset));
+        }
+        payload.truncate(length);
+        Ok(payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consts::GETTYSBURG_ADDRESS_BYTES;
+    use crate::polynomial::Polynomial;
+
+    #[test]
+    fn test_round_trip() {
+        let codec = EigenDaV0Codec;
+        for payload in [
+            &[][..],
+            &[0u8; 5],
+            b"hi",
+            &[0xff; 31],
+            GETTYSBURG_ADDRESS_BYTES,
+        ] {
+            let blob = codec.encode(payload).unwrap();
+            assert_eq!(codec.decode(&blob).unwrap(), payload);
+
+            // through the polynomial, as the blob is dispersed and retrieved
+            let polynomial = blob.to_polynomial().unwrap();
+            let elements = polynomial.to_vec();
+            let retrieved = Polynomial::new(&elements, elements.len() * BYTES_PER_FIELD_ELEMENT)
+                .unwrap()
+                .to_bytes_be();
+            assert!(retrieved.len() >= blob.len());
+            let retrieved = Blob::from_padded_bytes(&retrieved);
+            assert_eq!(codec.decode(&retrieved).unwrap(), payload);
+        }
+    }
+
+    #[test]
+    fn test_header() {
+        let blob = EigenDaV0Codec.encode(b"hello").unwrap();
+        let data = blob.get_blob_data();
+        assert_eq!(&data[..6], &[0, 0, 0, 0, 0, 5]);
+        assert!(data[6..32].iter().all(|byte| *byte == 0));
+        assert_eq!(&data[32..], &[0, b'h', b'e', b'l', b'l', b'o']);
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let codec = EigenDaV0Codec;
+        let data = codec.encode(b"hello").unwrap().get_blob_data();
+        let decode = |data: &[u8]| codec.decode(&Blob::from_padded_bytes(data));
+
+        assert_eq!(decode(&data[..31]), Err(BlobError::MissingHeader));
+        assert_eq!(
+            codec.decode(&Blob::new(data.clone())),
+            Err(BlobError::NotPaddedError)
+        );
+
+        let mut bad = data.clone();
+        bad[1] = 1;
+        assert_eq!(decode(&bad), Err(BlobError::UnsupportedVersion(1)));
+        for i in [0, 6, 31] {
+            let mut bad = data.clone();
+            bad[i] = 1;
+            assert_eq!(decode(&bad), Err(BlobError::InvalidHeader));
+        }
+
+        assert_eq!(
+            decode(&data[..36]),
+            Err(BlobError::PayloadTruncated {
+                expected: 5,
+                actual: 3
+            })
+        );
+
+        let mut bad = data.clone();
+        bad[32] = 1;
+        assert_eq!(decode(&bad), Err(BlobError::NonZeroPadding(32)));
+
+        // data after the payload
+        let mut longer = data.clone();
+        longer.resize(96, 0);
+        assert_eq!(decode(&longer).unwrap(), b"hello");
+        longer[38] = 1;
+        assert_eq!(decode(&longer), Err(BlobError::NonZeroPadding(38)));
+        longer[38] = 0;
+        longer[70] = 1;
+        assert_eq!(decode(&longer), Err(BlobError::NonZeroPadding(70)));
+        longer[64] = 1;
+        assert_eq!(decode(&longer), Err(BlobError::NonZeroPadding(64)));
+    }
+}
@@ -1,6 +1,24 @@
 pub const BYTES_PER_FIELD_ELEMENT: usize = 32;
 pub const SIZE_OF_G1_AFFINE_COMPRESSED: usize = 32; // in bytes
 pub const SIZE_OF_G2_AFFINE_COMPRESSED: usize = 64; // in bytes
+pub const SIZE_OF_G1_AFFINE_UNCOMPRESSED: usize = 64; // in bytes
+pub const SIZE_OF_G2_AFFINE_UNCOMPRESSED: usize = 128; // in bytes
+
+// gnark stores these flags in the two most significant bits of a compressed point
+pub const COMPRESSED_FLAG_MASK: u8 = 0b11 << 6;
+pub const COMPRESSED_INFINITY: u8 = 0b01 << 6;
+pub const COMPRESSED_SMALLEST: u8 = 0b10 << 6;
+pub const COMPRESSED_LARGEST: u8 = 0b11 << 6;
+pub const UNCOMPRESSED: u8 = 0b00 << 6;
+
+/// domain separator of the transcript of `Kzg::compute_challenge`
+pub const FIAT_SHAMIR_PROTOCOL_DOMAIN: &[u8] = b"EIGENDA_FSBLOBVERIFY_V1_";
+
+/// domain separator hashed into the challenge of `Kzg::verify_kzg_proof_batch`
+pub const KZG_BATCH_DOMAIN: &[u8] = b"RCKZGBATCH___V1_";
+
+/// domain separator hashed into the challenge of `Kzg::verify_commitment_equivalence_batch`
+pub const COMMITMENT_EQUIVALENCE_DOMAIN: &[u8] = b"RCKZGCOMEQUIV_V1_";
 
 #[allow(dead_code)]
 pub const GETTYSBURG_ADDRESS_BYTES: &[u8] = "Fourscore and seven years ago our fathers brought forth, on this continent, a new nation, conceived in liberty, and dedicated to the proposition that all men are created equal. Now we are engaged in a great civil war, testing whether that nation, or any nation so conceived, and so dedicated, can long endure. We are met on a great battle-field of that war. We have come to dedicate a portion of that field, as a final resting-place for those who here gave their lives, that that nation might live. It is altogether fitting and proper that we should do this. But, in a larger sense, we cannot dedicate, we cannot consecrate—we cannot hallow—this ground. The brave men, living and dead, who struggled here, have consecrated it far above our poor power to add or detract. The world will little note, nor long remember what we say here, but it can never forget what they did here. It is for us the living, rather, to be dedicated here to the unfinished work which they who fought here have thus far so nobly advanced. It is rather for us to be here dedicated to the great task remaining before us—that from these honored dead we take increased devotion to that cause for which they here gave the last full measure of devotion—that we here highly resolve that these dead shall not have died in vain—that this nation, under God, shall have a new birth of freedom, and that government of the people, by the people, for the people, shall not perish from the earth.".as_bytes();
@@ -0,0 +1,300 @@
+//! The multiplicative domains the polynomials are evaluated on, one shared
+//! instance per size. Nothing in here depends on the srs, so proofs over
+//! polynomials of any size can be computed from any `Kzg`.
+//!
+//! Opening at a root of unity divides by `w^i - w^m` for every `i`. Since
+//! `w^i - w^m = w^m * (w^(i - m) - 1)`, all those inverses come out of a single
+//! table of `1 / (1 - w^k)` per domain size, built once with a batch inversion.
+
+use crate::errors::KzgError;
+use ark_bn254::Fr;
+use ark_ff::{batch_inversion, FftField, Field};
+use ark_poly::domain::DomainCoeff;
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_std::{One, Zero};
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// The subgroup `<w>` of the `size`-th roots of unity of `Fr`, with the FFTs over it.
+///
+/// Domains are built once per size by `Domain::get` and shared afterwards. The
+/// tables of roots and inverses are only computed the first time they're needed.
+#[derive(Debug)]
+pub struct Domain {
+    inner: Radix2EvaluationDomain<Fr>,
+    /// `roots[i] = w^i`
+    roots: OnceLock<Vec<Fr>>,
+    /// `inv_one_minus_roots[k] = 1 / (1 - w^k)`, zero for `k = 0`
+    inv_one_minus_roots: OnceLock<Vec<Fr>>,
+}
+
+impl Domain {
+    fn new(size: usize) -> Result<Self, KzgError> {
+        let inner = Radix2EvaluationDomain::<Fr>::new(size)
+            .filter(|domain| domain.size() == size)
+            .ok_or(KzgError::InvalidDomainSize(size))?;
+        Ok(Self {
+            inner,
+            roots: OnceLock::new(),
+            inv_one_minus_roots: OnceLock::new(),
+        })
+    }
+
+    /// The domain of `size` elements, built on first use and shared afterwards
+    /// by every thread and every `Kzg` instance. `size` must be a power of 2 no
+    /// larger than `2^28`.
+    pub fn get(size: usize) -> Result<Arc<Self>, KzgError> {
+        static DOMAINS: OnceLock<RwLock<HashMap<usize, Arc<Domain>>>> = OnceLock::new();
+        let domains = DOMAINS.get_or_init(Default::default);
+
+        if let Some(cached) = domains.read().unwrap_or_else(|e| e.into_inner()).get(&size) {
+            return Ok(cached.clone());
+        }
+        let built = Arc::new(Self::new(size)?);
+        let mut domains = domains.write().unwrap_or_else(|e| e.into_inner());
+        Ok(domains.entry(size).or_insert(built).clone())
+    }
+
+    pub fn size(&self) -> usize {
+        self.inner.size()
+    }
+
+    pub fn log_size(&self) -> u32 {
+        self.inner.log_size_of_group
+    }
+
+    /// `w`, the primitive `size`-th root of unity
+    pub fn generator(&self) -> Fr {
+        self.inner.group_gen
+    }
+
+    /// `w^-1`
+    pub fn generator_inverse(&self) -> Fr {
+        self.inner.group_gen_inv
+    }
+
+    /// `1 / size`
+    pub fn size_inverse(&self) -> Fr {
+        self.inner.size_inv
+    }
+
+    /// `g`, the multiplicative generator of `Fr`. The coset `g * <w>` shares no
+    /// element with the domain, whatever its size.
+    pub fn coset_shift(&self) -> Fr {
+        Fr::GENERATOR
+    }
+
+    /// `[1, w, ..., w^(size - 1)]`
+    pub fn roots(&self) -> &[Fr] {
+        self.roots.get_or_init(|| self.inner.elements().collect())
+    }
+
+    /// `w^i`, for any `i`
+    pub fn element(&self, i: usize) -> Fr {
+        match self.roots.get() {
+            Some(roots) => roots[i % roots.len()],
+            None => self.generator().pow([(i % self.size()) as u64]),
+        }
+    }
+
+    /// Evaluations on the domain of the polynomial with these coefficients,
+    /// which are padded with zeros up to the size of the domain.
+    pub fn fft<T: DomainCoeff<Fr>>(&self, coeffs: &[T]) -> Result<Vec<T>, KzgError> {
+        if coeffs.len() > self.size() {
+            return Err(KzgError::TooLong {
+                max: self.size(),
+                actual: coeffs.len(),
+            });
+        }
+        Ok(self.inner.fft(coeffs))
+    }
+
+    /// Coefficients of the polynomial taking these evaluations on the domain.
+    pub fn ifft<T: DomainCoeff<Fr>>(&self, evals: &[T]) -> Result<Vec<T>, KzgError> {
+        if evals.len() != self.size() {
+            return Err(KzgError::LengthMismatch {
+                expected: self.size(),
+                actual: evals.len(),
+            });
+        }
+        Ok(self.inner.ifft(evals))
+    }
+
+    /// Evaluations on the coset `shift * <w>`, `evals[i] = p(shift * w^i)`.
+    pub fn coset_fft<T: DomainCoeff<Fr>>(
+        &self,
+        coeffs: &[T],
+        shift: Fr,
+    ) -> Result<Vec<T>, KzgError> {
+        let mut scaled = coeffs.to_vec();
+        scale_by_powers(&mut scaled, shift);
+        self.fft(&scaled)
+    }
+
+    /// Coefficients of the polynomial taking these evaluations on the coset
+    /// `shift * <w>`, the inverse of `coset_fft`.
+    pub fn coset_ifft<T: DomainCoeff<Fr>>(
+        &self,
+        evals: &[T],
+        shift: Fr,
+    ) -> Result<Vec<T>, KzgError> {
+        let shift_inverse = shift.inverse().ok_or(KzgError::ZeroCosetShift)?;
+        let mut coeffs = self.ifft(evals)?;
+        scale_by_powers(&mut coeffs, shift_inverse);
+        Ok(coeffs)
+    }
+
+    /// `w^-m`, the inverse of the root at `index`
+    pub(crate) fn inverse_root(&self, index: usize) -> Fr {
+        self.element(self.size() - index % self.size())
+    }
+
+    /// `1 / (w^i - w^m)` for every `i`, zero at `i = m`
+    pub(crate) fn inverse_differences(&self, index: usize) -> Vec<Fr> {
+        let inv_one_minus_roots = self.inv_one_minus_roots.get_or_init(|| {
+            let mut inverses: Vec<Fr> = self.roots().iter().map(|root| Fr::one() - root).collect();
+            batch_inversion(&mut inverses);
+            inverses
+        });
+        let size = self.size();
+        let minus_inverse_root = -self.inverse_root(index);
+        (0..size)
+            .map(|i| {
+                let k = (i + size - index) % size;
+                if k == 0 {
+                    Fr::zero()
+                } else {
+                    minus_inverse_root * inv_one_minus_roots[k]
+                }
+            })
+            .collect()
+    }
+}
+
+/// `values[i] *= factor^i`
+fn scale_by_powers<T: DomainCoeff<Fr>>(values: &mut [T], factor: Fr) {
+    let mut power = Fr::one();
+    for value in values.iter_mut() {
+        *value *= power;
+        power *= factor;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::polynomial::evaluate_coeffs;
+    use ark_bn254::G1Projective;
+    use ark_ec::Group;
+    use ark_std::str::FromStr;
+    use ark_std::UniformRand;
+
+    #[test]
+    fn test_inverse_differences() {
+        let domain = Domain::get(16).unwrap();
+        assert!(Arc::ptr_eq(&domain, &Domain::get(16).unwrap()));
+        assert_eq!(domain.size(), 16);
+
+        for index in [0, 1, 7, 15] {
+            let inverses = domain.inverse_differences(index);
+            for (i, inverse) in inverses.iter().enumerate() {
+                let expected = (domain.roots()[i] - domain.roots()[index])
+                    .inverse()
+                    .unwrap_or_default();
+                assert_eq!(*inverse, expected);
+            }
+            assert_eq!(
+                domain.inverse_root(index) * domain.roots()[index],
+                Fr::one()
+            );
+        }
+
+        assert_eq!(
+            Domain::get(12).unwrap_err(),
+            KzgError::InvalidDomainSize(12)
+        );
+    }
+
+    #[test]
+    fn test_generators_match_eigenda() {
+        // the primitive roots of unity of orders 2^0, ..., 2^3 and 2^28 used by the go encoder
+        let expected = [
+            (0, "1"),
+            (
+                1,
+                "21888242871839275222246405745257275088548364400416034343698204186575808495616",
+            ),
+            (
+                2,
+                "21888242871839275217838484774961031246007050428528088939761107053157389710902",
+            ),
+            (
+                3,
+                "19540430494807482326159819597004422086093766032135589407132600596362845576832",
+            ),
+            (
+                28,
+                "19103219067921713944291392827692070036145651957329286315305642004821462161904",
+            ),
+        ];
+        for (log_size, root) in expected {
+            let domain = Domain::get(1 << log_size).unwrap();
+            assert_eq!(domain.log_size(), log_size);
+            assert_eq!(domain.generator(), Fr::from_str(root).unwrap());
+        }
+
+        let domain = Domain::get(8).unwrap();
+        assert_eq!(domain.generator() * domain.generator_inverse(), Fr::one());
+        assert_eq!(domain.size_inverse() * Fr::from(8u64), Fr::one());
+        assert_eq!(domain.element(11), domain.roots()[3]);
+        assert_eq!(
+            Domain::get(4).unwrap().element(5),
+            Domain::get(4).unwrap().generator()
+        );
+    }
+
+    #[test]
+    fn test_ffts() {
+        let rng = &mut rand::thread_rng();
+        let domain = Domain::get(8).unwrap();
+        let coeffs: Vec<Fr> = (0..5).map(|_| Fr::rand(rng)).collect();
+        let evaluate = |x: Fr| evaluate_coeffs(&coeffs, x);
+
+        let evals = domain.fft(&coeffs).unwrap();
+        let shift = domain.coset_shift();
+        let coset_evals = domain.coset_fft(&coeffs, shift).unwrap();
+        for i in 0..8 {
+            assert_eq!(evals[i], evaluate(domain.element(i)));
+            assert_eq!(coset_evals[i], evaluate(shift * domain.element(i)));
+        }
+
+        let mut padded = coeffs.clone();
+        padded.resize(8, Fr::zero());
+        assert_eq!(domain.ifft(&evals).unwrap(), padded);
+        assert_eq!(domain.coset_ifft(&coset_evals, shift).unwrap(), padded);
+
+        // the same transforms work over the group
+        let points: Vec<G1Projective> = coeffs
+            .iter()
+            .map(|c| G1Projective::generator() * c)
+            .collect();
+        let point_evals = domain.fft(&points).unwrap();
+        assert_eq!(point_evals[3], G1Projective::generator() * evals[3]);
+
+        assert_eq!(
+            domain.fft(&[Fr::one(); 9]).unwrap_err(),
+            KzgError::TooLong { max: 8, actual: 9 }
+        );
+        assert_eq!(
+            domain.ifft(&evals[1..]).unwrap_err(),
+            KzgError::LengthMismatch {
+                expected: 8,
+                actual: 7
+            }
+        );
+        assert_eq!(
+            domain.coset_ifft(&evals, Fr::zero()).unwrap_err(),
+            KzgError::ZeroCosetShift
+        );
+    }
+}
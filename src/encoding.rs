@@ -0,0 +1,688 @@
+//! Parameters of the EigenDA erasure coding of a blob, as used by the go encoder.
+//!
+//! A blob of `n` field elements is extended to `chunk_length * num_chunks`
+//! evaluations, which are split into `num_chunks` chunks of `chunk_length`
+//! evaluations each, one chunk per coset of the subgroup of order `chunk_length`.
+//! Both are powers of 2 so every chunk is a coset of the evaluation domain.
+//!
+//! The go encoder orders the chunks by bit-reversing the evaluations: chunk `i`
+//! holds the coset `w^rev(i) * <w^num_chunks>`, where `w` generates the domain of
+//! all the evaluations and `rev` reverses the `log2(num_chunks)` low bits.
+
+use crate::consts::BYTES_PER_FIELD_ELEMENT;
+use crate::domain::Domain;
+use crate::errors::KzgError;
+use crate::helpers;
+use crate::polynomial::{Polynomial, PolynomialForm};
+use crate::traits::PointCodec;
+use ark_bn254::{Fr, G1Affine};
+use ark_ff::{batch_inversion, BigInteger, FftField, PrimeField};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+    Write,
+};
+use ark_std::{One, Zero};
+
+/// The chunking of an encoded blob, `EncodingParams` in the go encoder.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct EncodingParams {
+    chunk_length: u64,
+    num_chunks: u64,
+}
+
+impl EncodingParams {
+    /// `chunk_length` and `num_chunks` must both be powers of 2, and the
+    /// `chunk_length * num_chunks` evaluations must fit in a domain of `Fr`.
+    pub fn new(chunk_length: u64, num_chunks: u64) -> Result<Self, KzgError> {
+        let invalid = KzgError::InvalidEncodingParams {
+            chunk_length,
+            num_chunks,
+        };
+        if !chunk_length.is_power_of_two() || !num_chunks.is_power_of_two() {
+            return Err(invalid);
+        }
+        let log_evaluations = chunk_length.trailing_zeros() + num_chunks.trailing_zeros();
+        if log_evaluations > Fr::TWO_ADICITY {
+            return Err(invalid);
+        }
+        Ok(Self {
+            chunk_length,
+            num_chunks,
+        })
+    }
+
+    /// The smallest params with at least `min_chunk_length` evaluations per chunk
+    /// and at least `min_num_chunks` chunks, `ParamsFromMins` in the go encoder.
+    pub fn from_mins(min_chunk_length: u64, min_num_chunks: u64) -> Result<Self, KzgError> {
+        match (
+            min_chunk_length.checked_next_power_of_two(),
+            min_num_chunks.checked_next_power_of_two(),
+        ) {
+            (Some(chunk_length), Some(num_chunks)) => Self::new(chunk_length, num_chunks),
+            _ => Err(KzgError::InvalidEncodingParams {
+                chunk_length: min_chunk_length,
+                num_chunks: min_num_chunks,
+            }),
+        }
+    }
+
+    /// One chunk per operator, with the blob of `blob_length` bytes spread over
+    /// the chunks with no redundancy beyond the rounding to powers of 2.
+    pub fn from_blob_length(blob_length: u64, num_operators: u64) -> Result<Self, KzgError> {
+        if num_operators == 0 {
+            return Err(KzgError::InvalidEncodingParams {
+                chunk_length: 0,
+                num_chunks: 0,
+            });
+        }
+        let blob_elements = blob_length.div_ceil(BYTES_PER_FIELD_ELEMENT as u64);
+        Self::from_mins(blob_elements.div_ceil(num_operators), num_operators)
+    }
+
+    /// One chunk per operator, with enough redundancy that any operators holding
+    /// `quorum_threshold - adversary_threshold` percent of the chunks can recover
+    /// the blob of `blob_length` bytes. The coding ratio is
+    /// `100 / (quorum_threshold - adversary_threshold)`.
+    pub fn from_thresholds(
+        blob_length: u64,
+        num_operators: u64,
+        quorum_threshold: u8,
+        adversary_threshold: u8,
+    ) -> Result<Self, KzgError> {
+        if adversary_threshold >= quorum_threshold || quorum_threshold > 100 {
+            return Err(KzgError::InvalidThresholds {
+                quorum: quorum_threshold,
+                adversary: adversary_threshold,
+            });
+        }
+        if num_operators == 0 {
+            return Err(KzgError::InvalidEncodingParams {
+                chunk_length: 0,
+                num_chunks: 0,
+            });
+        }
+        let blob_elements = u128::from(blob_length.div_ceil(BYTES_PER_FIELD_ELEMENT as u64));
+        let recovering_operators =
+            u128::from(quorum_threshold - adversary_threshold) * u128::from(num_operators);
+        let min_chunk_length = (blob_elements * 100).div_ceil(recovering_operators);
+        let min_chunk_length =
+            u64::try_from(min_chunk_length).map_err(|_| KzgError::InvalidEncodingParams {
+                chunk_length: u64::MAX,
+                num_chunks: num_operators,
+            })?;
+        Self::from_mins(min_chunk_length, num_operators)
+    }
+
+    /// Checks that an srs of `srs_order` points is large enough to commit to
+    /// every evaluation, `ValidateEncodingParams` in the go encoder.
+    pub fn validate(&self, srs_order: u64) -> Result<(), KzgError> {
+        if self.num_evaluations() >= srs_order {
+            return Err(KzgError::SrsTooSmall {
+                required: self.num_evaluations(),
+                available: srs_order,
+            });
+        }
+        Ok(())
+    }
+
+    /// number of evaluations in one chunk
+    pub fn chunk_length(&self) -> u64 {
+        self.chunk_length
+    }
+
+    pub fn num_chunks(&self) -> u64 {
+        self.num_chunks
+    }
+
+    /// `chunk_length * num_chunks`, the size of the domain the encoded blob is
+    /// evaluated on
+    pub fn num_evaluations(&self) -> u64 {
+        self.chunk_length * self.num_chunks
+    }
+
+    /// `w^rev(chunk_index)`, the shift of the coset chunk `chunk_index` is evaluated on
+    pub fn chunk_coset_shift(&self, chunk_index: u64) -> Result<Fr, KzgError> {
+        if chunk_index >= self.num_chunks {
+            return Err(KzgError::IndexOutOfRange {
+                index: chunk_index,
+                len: self.num_chunks,
+            });
+        }
+        let domain = Domain::get(self.num_evaluations() as usize)?;
+        Ok(domain.element(reverse_bits(chunk_index, self.num_chunks) as usize))
+    }
+}
+
+/// `index` with its `log2(n)` low bits reversed, `n` a power of 2
+pub fn reverse_bits(index: u64, n: u64) -> u64 {
+    match n.trailing_zeros() {
+        0 => 0,
+        bits => index.reverse_bits() >> (u64::BITS - bits),
+    }
+}
+
+/// Extends the polynomial to the `num_evaluations` evaluations of the params and
+/// splits them into chunks the way the go encoder does. Chunk `i` holds
+/// `p(h * u^j)` for `j` in `0..chunk_length`, with `h = params.chunk_coset_shift(i)`
+/// and `u = w^num_chunks` generating the subgroup of order `chunk_length`.
+///
+/// The polynomial is read in its form, and can have any length up to
+/// `num_evaluations`. The longer the params, the more redundant the chunks.
+pub fn encode_chunks(
+    polynomial: &Polynomial,
+    params: &EncodingParams,
+) -> Result<Vec<Vec<Fr>>, KzgError> {
+    let num_evaluations = params.num_evaluations() as usize;
+    if polynomial.len() > num_evaluations {
+        return Err(KzgError::TooLong {
+            max: num_evaluations,
+            actual: polynomial.len(),
+        });
+    }
+    let coeffs = polynomial.to_coeff_form()?.to_vec();
+    let evaluations = Domain::get(num_evaluations)?.fft(&coeffs)?;
+
+    let num_chunks = params.num_chunks();
+    Ok((0..num_chunks)
+        .map(|i| {
+            evaluations
+                .iter()
+                .skip(reverse_bits(i, num_chunks) as usize)
+                .step_by(num_chunks as usize)
+                .copied()
+                .collect()
+        })
+        .collect())
+}
+
+/// Recovers the polynomial of `polynomial_length` elements from any chunks of
+/// `encode_chunks`, given with their indices, that hold at least
+/// `polynomial_length` evaluations between them. The polynomial is returned in
+/// coefficient form, padded to a power of 2 as `Polynomial::new` does.
+///
+/// The evaluations of the missing chunks are erased with the vanishing polynomial
+/// `Z` of their cosets: `E * Z` and `p * Z` agree on the whole domain and have a
+/// low enough degree, so `p * Z` is interpolated from `E * Z` and divided by `Z`
+/// on a coset where `Z` has no root. Chunks beyond the ones needed must agree
+/// with the recovered polynomial.
+pub fn recover_polynomial(
+    indices: &[u64],
+    chunks: &[Vec<Fr>],
+    params: &EncodingParams,
+    polynomial_length: usize,
+) -> Result<Polynomial, KzgError> {
+    if indices.len() != chunks.len() {
+        return Err(KzgError::LengthMismatch {
+            expected: indices.len(),
+            actual: chunks.len(),
+        });
+    }
+    let chunk_length = params.chunk_length() as usize;
+    let num_chunks = params.num_chunks() as usize;
+    let num_evaluations = params.num_evaluations() as usize;
+    let polynomial_length = polynomial_length.max(1).next_power_of_two();
+    if polynomial_length > num_evaluations {
+        return Err(KzgError::TooLong {
+            max: num_evaluations,
+            actual: polynomial_length,
+        });
+    }
+
+    let domain = Domain::get(num_evaluations)?;
+    let mut evaluations = vec![Fr::zero(); num_evaluations];
+    let mut received = vec![false; num_chunks];
+    for (&index, chunk) in indices.iter().zip(chunks) {
+        if index >= params.num_chunks() {
+            return Err(KzgError::IndexOutOfRange {
+                index,
+                len: params.num_chunks(),
+            });
+        }
+        if chunk.len() != chunk_length {
+            return Err(KzgError::LengthMismatch {
+                expected: chunk_length,
+                actual: chunk.len(),
+            });
+        }
+        let shift_index = reverse_bits(index, params.num_chunks()) as usize;
+        for (j, evaluation) in chunk.iter().enumerate() {
+            evaluations[shift_index + j * num_chunks] = *evaluation;
+        }
+        received[index as usize] = true;
+    }
+
+    let available = received.iter().filter(|&&received| received).count();
+    let required = polynomial_length.div_ceil(chunk_length);
+    if available < required {
+        return Err(KzgError::NotEnoughChunks {
+            required: required as u64,
+            available: available as u64,
+        });
+    }
+
+    let coeffs = if available == num_chunks {
+        domain.ifft(&evaluations)?
+    } else {
+        // Z(X) = prod (X^chunk_length - h^chunk_length) over the missing cosets,
+        // a polynomial in X^chunk_length
+        let mut vanishing = vec![Fr::one()];
+        for index in (0..num_chunks).filter(|&index| !received[index]) {
+            let shift_index = reverse_bits(index as u64, params.num_chunks()) as usize;
+            let root = -domain.element(shift_index * chunk_length);
+            vanishing.push(Fr::zero());
+            for k in (1..vanishing.len()).rev() {
+                let carry = vanishing[k - 1] * root;
+                vanishing[k] += carry;
+            }
+        }
+        vanishing.reverse();
+        let mut vanishing_coeffs = vec![Fr::zero(); num_evaluations];
+        for (k, coeff) in vanishing.iter().enumerate() {
+            vanishing_coeffs[k * chunk_length] = *coeff;
+        }
+
+        // E * Z, zero on the missing cosets
+        let vanishing_evals = domain.fft(&vanishing_coeffs)?;
+        let erased: Vec<Fr> = evaluations
+            .iter()
+            .zip(&vanishing_evals)
+            .map(|(evaluation, vanishing)| *evaluation * vanishing)
+            .collect();
+        let erased_coeffs = domain.ifft(&erased)?;
+
+        let shift = domain.coset_shift();
+        let mut quotient = domain.coset_fft(&erased_coeffs, shift)?;
+        let mut vanishing_inverses = domain.coset_fft(&vanishing_coeffs, shift)?;
+        batch_inversion(&mut vanishing_inverses);
+        for (value, inverse) in quotient.iter_mut().zip(&vanishing_inverses) {
+            *value *= inverse;
+        }
+        domain.coset_ifft(&quotient, shift)?
+    };
+
+    if coeffs[polynomial_length..]
+        .iter()
+        .any(|coeff| !coeff.is_zero())
+    {
+        return Err(KzgError::InconsistentChunks);
+    }
+    Ok(Polynomial::new_with_form(
+        &coeffs[..polynomial_length],
+        polynomial_length * BYTES_PER_FIELD_ELEMENT,
+        PolynomialForm::Coeff,
+    )?)
+}
+
+/// A chunk as it goes over the wire, `Frame` in the go encoder. The chunk is
+/// held as the coefficients of the polynomial interpolating its evaluations,
+/// along with the multiproof of its coset, see `Kzg::verify_frame`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Frame {
+    pub proof: G1Affine,
+    pub coeffs: Vec<Fr>,
+}
+
+impl Frame {
+    /// The frame of chunk `frame_index` of `encode_chunks`, interpolating its
+    /// evaluations on the coset of the chunk.
+    pub fn from_evaluations(
+        proof: G1Affine,
+        evaluations: &[Fr],
+        frame_index: u64,
+        params: &EncodingParams,
+    ) -> Result<Self, KzgError> {
+        let shift = params.chunk_coset_shift(frame_index)?;
+        let coeffs = Domain::get(evaluations.len())?.coset_ifft(evaluations, shift)?;
+        Ok(Self { proof, coeffs })
+    }
+
+    /// the evaluations of chunk `frame_index` of `encode_chunks` held by the frame
+    pub fn evaluations(
+        &self,
+        frame_index: u64,
+        params: &EncodingParams,
+    ) -> Result<Vec<Fr>, KzgError> {
+        let shift = params.chunk_coset_shift(frame_index)?;
+        Domain::get(self.coeffs.len())?.coset_fft(&self.coeffs, shift)
+    }
+
+    /// The gnark layout of the go encoder, `SerializeGnark`: the compressed
+    /// proof followed by the 32 byte big-endian coefficients.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(
+            G1Affine::COMPRESSED_SIZE + self.coeffs.len() * BYTES_PER_FIELD_ELEMENT,
+        );
+        bytes.extend(self.proof.to_compressed_be());
+        for coeff in &self.coeffs {
+            bytes.extend(coeff.into_bigint().to_bytes_be());
+        }
+        bytes
+    }
+
+    /// Reads back what `to_bytes` wrote, rejecting proofs outside the subgroup
+    /// and coefficients that are not canonical.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, KzgError> {
+        if bytes.len() <= G1Affine::COMPRESSED_SIZE {
+            return Err(KzgError::TruncatedInput("frame"));
+        }
+        let (proof_bytes, coeff_bytes) = bytes.split_at(G1Affine::COMPRESSED_SIZE);
+        if coeff_bytes.len() % BYTES_PER_FIELD_ELEMENT != 0 {
+            return Err(KzgError::TruncatedInput("coefficient"));
+        }
+        let proof = G1Affine::from_compressed_be(proof_bytes)
+            .map_err(|source| KzgError::InvalidPoint { index: 0, source })?;
+        let coeffs = coeff_bytes
+            .chunks(BYTES_PER_FIELD_ELEMENT)
+            .map(helpers::set_bytes_canonical_manual)
+            .collect::<Result<_, _>>()?;
+        Ok(Self { proof, coeffs })
+    }
+}
+
+impl CanonicalSerialize for EncodingParams {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.chunk_length
+            .serialize_with_mode(&mut writer, compress)?;
+        self.num_chunks.serialize_with_mode(writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.chunk_length.serialized_size(compress) + self.num_chunks.serialized_size(compress)
+    }
+}
+
+impl Valid for EncodingParams {
+    fn check(&self) -> Result<(), SerializationError> {
+        Self::new(self.chunk_length, self.num_chunks)
+            .map(|_| ())
+            .map_err(|_| SerializationError::InvalidData)
+    }
+}
+
+impl CanonicalDeserialize for EncodingParams {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let chunk_length = u64::deserialize_with_mode(&mut reader, compress, validate)?;
+        let num_chunks = u64::deserialize_with_mode(reader, compress, validate)?;
+        let params = Self {
+            chunk_length,
+            num_chunks,
+        };
+        if validate == Validate::Yes {
+            params.check()?;
+        }
+        Ok(params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blob::Blob;
+    use crate::consts::GETTYSBURG_ADDRESS_BYTES;
+    use crate::kzg::Kzg;
+    use crate::polynomial::evaluate_coeffs;
+
+    #[test]
+    fn test_constructors() {
+        let params = EncodingParams::new(16, 4).unwrap();
+        assert_eq!(params.chunk_length(), 16);
+        assert_eq!(params.num_chunks(), 4);
+        assert_eq!(params.num_evaluations(), 64);
+        assert_eq!(EncodingParams::from_mins(9, 3), Ok(params));
+        assert_eq!(EncodingParams::from_mins(0, 1).unwrap().chunk_length(), 1);
+
+        // 1000 bytes are 32 field elements, 8 per operator
+        assert_eq!(
+            EncodingParams::from_blob_length(1000, 4),
+            EncodingParams::new(8, 4)
+        );
+        // a coding ratio of 100 / (80 - 30) = 2 doubles the chunks
+        assert_eq!(
+            EncodingParams::from_thresholds(1000, 4, 80, 30),
+            EncodingParams::new(16, 4)
+        );
+        // 100 / (55 - 33) rounds up the chunk length
+        assert_eq!(
+            EncodingParams::from_thresholds(1000, 3, 55, 33),
+            EncodingParams::new(64, 4)
+        );
+    }
+
+    #[test]
+    fn test_invalid_params() {
+        for (chunk_length, num_chunks) in [(0, 4), (16, 3), (1 << 20, 1 << 10)] {
+            assert_eq!(
+                EncodingParams::new(chunk_length, num_chunks),
+                Err(KzgError::InvalidEncodingParams {
+                    chunk_length,
+                    num_chunks
+                })
+            );
+        }
+        assert!(EncodingParams::new(1 << 20, 1 << 8).is_ok());
+        assert!(EncodingParams::from_mins(u64::MAX, 2).is_err());
+        assert_eq!(EncodingParams::from_mins(4, 0), EncodingParams::new(4, 1));
+        assert!(EncodingParams::from_blob_length(1000, 0).is_err());
+        assert_eq!(
+            EncodingParams::from_thresholds(1000, 4, 30, 30),
+            Err(KzgError::InvalidThresholds {
+                quorum: 30,
+                adversary: 30
+            })
+        );
+        assert!(EncodingParams::from_thresholds(1000, 4, 101, 30).is_err());
+
+        let params = EncodingParams::new(16, 4).unwrap();
+        assert_eq!(params.validate(65), Ok(()));
+        assert_eq!(
+            params.validate(64),
+            Err(KzgError::SrsTooSmall {
+                required: 64,
+                available: 64
+            })
+        );
+    }
+
+    #[test]
+    fn test_reverse_bits() {
+        assert_eq!(reverse_bits(0, 1), 0);
+        assert_eq!(reverse_bits(1, 2), 1);
+        let reversed: Vec<u64> = (0..8).map(|i| reverse_bits(i, 8)).collect();
+        assert_eq!(reversed, vec![0, 4, 2, 6, 1, 5, 3, 7]);
+        assert_eq!(reverse_bits(1, 1 << 28), 1 << 27);
+    }
+
+    #[test]
+    fn test_encode_chunks() {
+        let mut kzg = Kzg::setup(true).unwrap();
+        let blob = Blob::from_bytes_and_pad(GETTYSBURG_ADDRESS_BYTES);
+        let polynomial = blob.to_polynomial().unwrap();
+        let commitment = kzg.commit(&polynomial).unwrap();
+        let coeffs = polynomial.to_coeff_form().unwrap().to_vec();
+        let evaluate = |x: Fr| evaluate_coeffs(&coeffs, x);
+
+        // twice the polynomial's 64 evaluations, in 8 chunks of 16
+        let params = EncodingParams::new(16, 8).unwrap();
+        let chunks = encode_chunks(&polynomial, &params).unwrap();
+        assert_eq!(chunks.len(), 8);
+
+        let domain = Domain::get(128).unwrap();
+        let subgroup = Domain::get(16).unwrap();
+        kzg.set_encoding_params(params).unwrap();
+        for (i, chunk) in chunks.iter().enumerate() {
+            let shift = params.chunk_coset_shift(i as u64).unwrap();
+            assert_eq!(shift, domain.element(reverse_bits(i as u64, 8) as usize));
+            assert_eq!(chunk.len(), 16);
+            for (j, evaluation) in chunk.iter().enumerate() {
+                assert_eq!(*evaluation, evaluate(shift * subgroup.element(j)));
+            }
+
+            // the chunk opens against the commitment with the proof of its coset
+            let proof = kzg
+                .compute_coset_proof(&polynomial, reverse_bits(i as u64, 8))
+                .unwrap();
+            assert_eq!(
+                kzg.verify_coset_proof(commitment, proof, shift, chunk),
+                Ok(true)
+            );
+        }
+
+        // the form of the polynomial is respected
+        let coeff_form = polynomial.to_coeff_form().unwrap();
+        assert_eq!(encode_chunks(&coeff_form, &params), Ok(chunks));
+
+        assert_eq!(
+            encode_chunks(&polynomial, &EncodingParams::new(8, 4).unwrap()),
+            Err(KzgError::TooLong {
+                max: 32,
+                actual: 64
+            })
+        );
+        assert_eq!(
+            params.chunk_coset_shift(8),
+            Err(KzgError::IndexOutOfRange { index: 8, len: 8 })
+        );
+    }
+
+    #[test]
+    fn test_recover_polynomial() {
+        use ark_std::UniformRand;
+
+        let rng = &mut rand::thread_rng();
+        let elements: Vec<Fr> = (0..60).map(|_| Fr::rand(rng)).collect();
+        let polynomial = Polynomial::new(&elements, 60 * BYTES_PER_FIELD_ELEMENT).unwrap();
+        let coeff_form = polynomial.to_coeff_form().unwrap();
+
+        // 64 evaluations in chunks of 8, any 8 of the 16 chunks recover them
+        let params = EncodingParams::new(8, 16).unwrap();
+        let chunks = encode_chunks(&polynomial, &params).unwrap();
+        for indices in [
+            (0..8).collect::<Vec<u64>>(),
+            (8..16).collect(),
+            vec![15, 3, 9, 4, 0, 12, 7, 10],
+            vec![1, 2, 3, 5, 6, 8, 11, 13, 14],
+            (0..16).collect(),
+        ] {
+            let received: Vec<Vec<Fr>> = indices
+                .iter()
+                .map(|&i| chunks[i as usize].clone())
+                .collect();
+            let recovered = recover_polynomial(&indices, &received, &params, 60).unwrap();
+            assert_eq!(recovered.form(), PolynomialForm::Coeff);
+            assert_eq!(recovered.to_vec(), coeff_form.to_vec());
+            assert_eq!(
+                recovered.to_eval_form().unwrap().to_vec(),
+                polynomial.to_vec()
+            );
+        }
+
+        // a repeated chunk doesn't count twice
+        let indices = [0, 1, 2, 3, 4, 5, 6, 6];
+        let received: Vec<Vec<Fr>> = indices.iter().map(|&i| chunks[i].clone()).collect();
+        let indices: Vec<u64> = indices.iter().map(|&i| i as u64).collect();
+        assert_eq!(
+            recover_polynomial(&indices, &received, &params, 64),
+            Err(KzgError::NotEnoughChunks {
+                required: 8,
+                available: 7
+            })
+        );
+
+        // a wrong evaluation in the redundant chunks is caught
+        let indices: Vec<u64> = (0..10).collect();
+        let mut received = chunks[..10].to_vec();
+        received[9][2] += Fr::one();
+        assert_eq!(
+            recover_polynomial(&indices, &received, &params, 64),
+            Err(KzgError::InconsistentChunks)
+        );
+
+        assert_eq!(
+            recover_polynomial(&[16], &chunks[..1], &params, 8),
+            Err(KzgError::IndexOutOfRange { index: 16, len: 16 })
+        );
+        assert_eq!(
+            recover_polynomial(&[0], &[vec![Fr::one(); 4]], &params, 8),
+            Err(KzgError::LengthMismatch {
+                expected: 8,
+                actual: 4
+            })
+        );
+        assert_eq!(
+            recover_polynomial(&[0, 1], &chunks[..1], &params, 8),
+            Err(KzgError::LengthMismatch {
+                expected: 2,
+                actual: 1
+            })
+        );
+        assert_eq!(
+            recover_polynomial(&[0], &chunks[..1], &params, 200),
+            Err(KzgError::TooLong {
+                max: 128,
+                actual: 256
+            })
+        );
+    }
+
+    #[test]
+    fn test_frame_bytes() {
+        use ark_ec::AffineRepr;
+
+        let frame = Frame {
+            proof: G1Affine::generator(),
+            coeffs: vec![Fr::from(1u64), -Fr::one(), Fr::from(258u64)],
+        };
+        let bytes = frame.to_bytes();
+        assert_eq!(bytes.len(), 32 + 3 * 32);
+        assert_eq!(&bytes[..32], &G1Affine::generator().to_compressed_be()[..]);
+        assert_eq!(bytes[63], 1);
+        assert_eq!(&bytes[126..], &[1, 2]);
+        assert_eq!(Frame::from_bytes(&bytes), Ok(frame.clone()));
+
+        assert_eq!(
+            Frame::from_bytes(&bytes[..32]),
+            Err(KzgError::TruncatedInput("frame"))
+        );
+        assert_eq!(
+            Frame::from_bytes(&bytes[..100]),
+            Err(KzgError::TruncatedInput("coefficient"))
+        );
+        let mut bad = bytes.clone();
+        bad[64..96].copy_from_slice(&[0xff; 32]);
+        assert_eq!(
+            Frame::from_bytes(&bad),
+            Err(KzgError::Polynomial(
+                crate::errors::PolynomialError::NonCanonicalFieldElement
+            ))
+        );
+        let mut bad = bytes.clone();
+        bad[..32].copy_from_slice(&[0xff; 32]);
+        assert!(matches!(
+            Frame::from_bytes(&bad),
+            Err(KzgError::InvalidPoint { index: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_serialization() {
+        let params = EncodingParams::new(16, 4).unwrap();
+        let mut bytes = vec![];
+        params.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(
+            EncodingParams::deserialize_compressed(bytes.as_slice()).unwrap(),
+            params
+        );
+
+        let mut bytes = vec![];
+        (16u64, 3u64).serialize_compressed(&mut bytes).unwrap();
+        assert!(EncodingParams::deserialize_compressed(bytes.as_slice()).is_err());
+    }
+}
@@ -1,10 +1,26 @@
-use std::{error::Error, fmt};
+use std::{error::Error, fmt, io};
 
 #[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
 pub enum BlobError {
     NotPaddedError,
     AlreadyPaddedError,
-    GenericError(String),
+    /// the blob data couldn't be turned into a polynomial
+    Polynomial(PolynomialError),
+    /// the blob is too short to hold the payload header
+    MissingHeader,
+    /// the header is not the one of the codec, see `codec::PayloadCodec`
+    InvalidHeader,
+    UnsupportedVersion(u8),
+    /// the payload length doesn't fit in the header
+    PayloadTooLong(usize),
+    /// the header claims more payload bytes than the blob holds
+    PayloadTruncated {
+        expected: usize,
+        actual: usize,
+    },
+    /// the byte at this offset of the blob must be zero
+    NonZeroPadding(usize),
 }
 
 impl fmt::Display for BlobError {
@@ -12,18 +28,55 @@ impl fmt::Display for BlobError {
         match *self {
             BlobError::NotPaddedError => write!(f, "tried to execute on non padded blob"),
             BlobError::AlreadyPaddedError => write!(f, "tried to execute on already padded blob"),
-            BlobError::GenericError(ref msg) => write!(f, "generic error: {}", msg),
+            BlobError::Polynomial(_) => write!(f, "invalid blob polynomial"),
+            BlobError::MissingHeader => write!(f, "blob is too short to hold a payload header"),
+            BlobError::InvalidHeader => write!(f, "invalid payload header"),
+            BlobError::UnsupportedVersion(version) => {
+                write!(f, "unsupported payload encoding version {}", version)
+            }
+            BlobError::PayloadTooLong(len) => {
+                write!(f, "payload of {} bytes is too long to encode", len)
+            }
+            BlobError::PayloadTruncated { expected, actual } => write!(
+                f,
+                "payload truncated: expected {} bytes, found {}",
+                expected, actual
+            ),
+            BlobError::NonZeroPadding(offset) => {
+                write!(f, "padding byte at offset {} is not zero", offset)
+            }
         }
     }
 }
 
-impl Error for BlobError {}
+impl Error for BlobError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            BlobError::Polynomial(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<PolynomialError> for BlobError {
+    fn from(err: PolynomialError) -> Self {
+        BlobError::Polynomial(err)
+    }
+}
 
 #[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
 pub enum PolynomialError {
     SerializationFromStringError,
-    CommitError(String),
-    GenericError(String),
+    /// a polynomial needs at least one element
+    EmptyElements,
+    /// the bytes don't hold a field element below the modulus
+    NonCanonicalFieldElement,
+    /// a field element must be read from exactly `expected` bytes
+    InvalidLength {
+        expected: usize,
+        actual: usize,
+    },
 }
 
 impl fmt::Display for PolynomialError {
@@ -32,8 +85,13 @@ impl fmt::Display for PolynomialError {
             PolynomialError::SerializationFromStringError => {
                 write!(f, "couldn't load string to fr vector")
             }
-            PolynomialError::CommitError(ref msg) => write!(f, "Commitment error: {}", msg),
-            PolynomialError::GenericError(ref msg) => write!(f, "generic error: {}", msg),
+            PolynomialError::EmptyElements => write!(f, "elements are empty"),
+            PolynomialError::NonCanonicalFieldElement => {
+                write!(f, "bytes are not a canonical field element")
+            }
+            PolynomialError::InvalidLength { expected, actual } => {
+                write!(f, "expected {} bytes, got {}", expected, actual)
+            }
         }
     }
 }
@@ -41,25 +99,286 @@ impl fmt::Display for PolynomialError {
 impl Error for PolynomialError {}
 
 #[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
 pub enum KzgError {
-    CommitError(String),
-    SerializationError(String),
-    FftError(String),
-    GenericError(String),
+    /// reading or writing failed, `io::Error` itself is neither `Clone` nor `PartialEq`
+    Io {
+        kind: io::ErrorKind,
+        message: String,
+    },
+    /// an error raised while handling the file at `path`
+    File {
+        path: String,
+        source: Box<KzgError>,
+    },
+    /// the point at `index` of a point file couldn't be decoded
+    InvalidPoint {
+        index: usize,
+        source: PointDecodingError,
+    },
+    /// the input stops in the middle of a point or a size
+    TruncatedInput(&'static str),
+    NotEnoughPoints {
+        expected: usize,
+        found: usize,
+    },
+    /// arkworks couldn't (de)serialize a value
+    Serialization(String),
+    /// the lagrange bases read for this domain size are invalid
+    LagrangeBases {
+        size: usize,
+        source: Box<KzgError>,
+    },
+    /// the lagrange bases weren't derived from the loaded srs
+    LagrangeBasesMismatch(usize),
+    MainnetSrsNotBundled,
+    /// neither g2 point file was given
+    MissingG2Points,
+    /// more srs points are needed than were loaded
+    SrsTooSmall {
+        required: u64,
+        available: u64,
+    },
+    /// the g2 points don't hold [tau^n]_2 for this n
+    G2PowerUnavailable(u64),
+    /// the g1 and g2 points weren't generated from the same tau
+    SrsTauMismatch,
+    /// no evaluation domain has this size: it isn't a power of 2 or is above 2^28
+    InvalidDomainSize(usize),
+    /// two inputs that go together have different lengths
+    LengthMismatch {
+        expected: usize,
+        actual: usize,
+    },
+    /// an input is longer than it can be
+    TooLong {
+        max: usize,
+        actual: usize,
+    },
+    IndexOutOfRange {
+        index: u64,
+        len: u64,
+    },
+    ZeroCosetShift,
+    ZeroRootOfUnity,
+    /// the encoding params have not been set with the data_setup functions
+    SetupIncomplete,
+    InvalidEncodingParams {
+        chunk_length: u64,
+        num_chunks: u64,
+    },
+    /// security thresholds in percent, the quorum must be above the adversary
+    InvalidThresholds {
+        quorum: u8,
+        adversary: u8,
+    },
+    /// fewer distinct chunks than needed to recover the polynomial
+    NotEnoughChunks {
+        required: u64,
+        available: u64,
+    },
+    /// the chunks are not the evaluations of a polynomial of the expected length
+    InconsistentChunks,
+    MemoryBudgetTooSmall(usize),
+    Blob(BlobError),
+    Polynomial(PolynomialError),
 }
 
 impl fmt::Display for KzgError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match *self {
-            KzgError::CommitError(ref msg) => write!(f, "Commitment error: {}", msg),
-            KzgError::SerializationError(ref msg) => write!(f, "Serialization error: {}", msg),
-            KzgError::FftError(ref msg) => write!(f, "FFT error: {}", msg),
-            KzgError::GenericError(ref msg) => write!(f, "Generic error: {}", msg),
+            KzgError::Io { ref message, .. } => write!(f, "io error: {}", message),
+            KzgError::File {
+                ref path,
+                ref source,
+            } => write!(f, "{}: {}", path, source),
+            KzgError::InvalidPoint { index, ref source } => {
+                write!(f, "point {}: {}", index, source)
+            }
+            KzgError::TruncatedInput(what) => write!(f, "input ends with a truncated {}", what),
+            KzgError::NotEnoughPoints { expected, found } => {
+                write!(f, "expected {} points, found {}", expected, found)
+            }
+            KzgError::Serialization(ref msg) => write!(f, "Serialization error: {}", msg),
+            KzgError::LagrangeBases { size, ref source } => {
+                write!(f, "lagrange bases of size {}: {}", size, source)
+            }
+            KzgError::LagrangeBasesMismatch(size) => {
+                write!(f, "lagrange bases of size {} don't match the srs", size)
+            }
+            KzgError::MainnetSrsNotBundled => write!(
+                f,
+                "mainnet SRS is not bundled, load it with setup_from_files"
+            ),
+            KzgError::MissingG2Points => {
+                write!(f, "both g2 point files are empty, need at least one")
+            }
+            KzgError::SrsTooSmall {
+                required,
+                available,
+            } => write!(
+                f,
+                "needs {} srs points but only {} are available",
+                required, available
+            ),
+            KzgError::G2PowerUnavailable(n) => {
+                write!(f, "G2 error: tau^{} is not in the loaded g2 points", n)
+            }
+            KzgError::SrsTauMismatch => {
+                write!(f, "the g1 and g2 points don't share the same tau")
+            }
+            KzgError::InvalidDomainSize(size) => write!(f, "FFT error: no domain of size {}", size),
+            KzgError::LengthMismatch { expected, actual } => {
+                write!(f, "expected a length of {}, got {}", expected, actual)
+            }
+            KzgError::TooLong { max, actual } => {
+                write!(f, "expected a length of at most {}, got {}", max, actual)
+            }
+            KzgError::IndexOutOfRange { index, len } => {
+                write!(f, "index {} is out of range for length {}", index, len)
+            }
+            KzgError::ZeroCosetShift => write!(f, "coset shift can't be zero"),
+            KzgError::ZeroRootOfUnity => write!(f, "root of unity can't be zero"),
+            KzgError::SetupIncomplete => {
+                write!(f, "setup is not complete, run the data_setup functions")
+            }
+            KzgError::InvalidEncodingParams {
+                chunk_length,
+                num_chunks,
+            } => write!(
+                f,
+                "invalid encoding params: {} chunks of length {}",
+                num_chunks, chunk_length
+            ),
+            KzgError::InvalidThresholds { quorum, adversary } => write!(
+                f,
+                "invalid thresholds: quorum of {}% and adversary of {}%",
+                quorum, adversary
+            ),
+            KzgError::NotEnoughChunks {
+                required,
+                available,
+            } => write!(
+                f,
+                "not enough chunks: need {}, found {}",
+                required, available
+            ),
+            KzgError::InconsistentChunks => {
+                write!(f, "chunks don't encode a polynomial of the expected length")
+            }
+            KzgError::MemoryBudgetTooSmall(budget) => write!(
+                f,
+                "memory budget of {} bytes is too small for the precomputed tables",
+                budget
+            ),
+            KzgError::Blob(_) => write!(f, "invalid blob"),
+            KzgError::Polynomial(_) => write!(f, "invalid polynomial"),
         }
     }
 }
 
-impl Error for KzgError {}
+impl Error for KzgError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            KzgError::File { source, .. } | KzgError::LagrangeBases { source, .. } => {
+                Some(source.as_ref())
+            }
+            KzgError::InvalidPoint { source, .. } => Some(source),
+            KzgError::Blob(err) => Some(err),
+            KzgError::Polynomial(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl KzgError {
+    /// adds the path of the file being handled to the error
+    pub(crate) fn in_file(self, path: &str) -> Self {
+        KzgError::File {
+            path: path.to_string(),
+            source: Box::new(self),
+        }
+    }
+}
+
+impl From<io::Error> for KzgError {
+    fn from(err: io::Error) -> Self {
+        KzgError::Io {
+            kind: err.kind(),
+            message: err.to_string(),
+        }
+    }
+}
+
+impl From<ark_serialize::SerializationError> for KzgError {
+    fn from(err: ark_serialize::SerializationError) -> Self {
+        KzgError::Serialization(err.to_string())
+    }
+}
+
+impl From<BlobError> for KzgError {
+    fn from(err: BlobError) -> Self {
+        KzgError::Blob(err)
+    }
+}
+
+impl From<PolynomialError> for KzgError {
+    fn from(err: PolynomialError) -> Self {
+        KzgError::Polynomial(err)
+    }
+}
+
+/// Every way decoding a compressed point can fail.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum PointDecodingError {
+    InvalidLength {
+        expected: usize,
+        actual: usize,
+    },
+    InvalidCompressionFlag(u8),
+    NonCanonicalCoordinate,
+    NoSquareRoot,
+    NotOnCurve,
+    NotInSubgroup,
+    InvalidInfinityEncoding,
+    /// arkworks rejected a little-endian encoding
+    InvalidArkworksEncoding(String),
+}
+
+impl fmt::Display for PointDecodingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            PointDecodingError::InvalidLength { expected, actual } => {
+                write!(f, "expected {} bytes, got {}", expected, actual)
+            }
+            PointDecodingError::InvalidCompressionFlag(flag) => {
+                write!(f, "invalid compression flag: {:#04x}", flag)
+            }
+            PointDecodingError::NonCanonicalCoordinate => {
+                write!(f, "coordinate is not smaller than the field modulus")
+            }
+            PointDecodingError::NoSquareRoot => {
+                write!(
+                    f,
+                    "invalid compressed coordinate: square root doesn't exist"
+                )
+            }
+            PointDecodingError::NotOnCurve => write!(f, "point is not on the curve"),
+            PointDecodingError::NotInSubgroup => {
+                write!(f, "point is not in the prime order subgroup")
+            }
+            PointDecodingError::InvalidInfinityEncoding => {
+                write!(f, "point at infinity not coded properly")
+            }
+            PointDecodingError::InvalidArkworksEncoding(ref msg) => {
+                write!(f, "invalid arkworks encoding: {}", msg)
+            }
+        }
+    }
+}
+
+impl Error for PointDecodingError {}
 
 #[cfg(test)]
 mod tests {
@@ -72,40 +391,34 @@ mod tests {
     }
 
     #[test]
-    fn test_polynomial_error_commit() {
-        let msg = String::from("test commit error");
-        let error = PolynomialError::CommitError(msg.clone());
-        assert_eq!(format!("{}", error), format!("Commitment error: {}", msg));
+    fn test_polynomial_error_empty_elements() {
+        let error = PolynomialError::EmptyElements;
+        assert_eq!(format!("{}", error), "elements are empty");
     }
 
     #[test]
-    fn test_polynomial_error_generic() {
-        let msg = String::from("test generic error");
-        let error = PolynomialError::GenericError(msg.clone());
-        assert_eq!(format!("{}", error), format!("generic error: {}", msg));
+    fn test_polynomial_error_invalid_length() {
+        let error = PolynomialError::InvalidLength {
+            expected: 32,
+            actual: 31,
+        };
+        assert_eq!(format!("{}", error), "expected 32 bytes, got 31");
     }
 
     #[test]
     fn test_polynomial_error_equality() {
         let error1 = PolynomialError::SerializationFromStringError;
         let error2 = PolynomialError::SerializationFromStringError;
-        let error3 = PolynomialError::CommitError(String::from("error"));
+        let error3 = PolynomialError::EmptyElements;
         assert_eq!(error1, error2);
         assert_ne!(error1, error3);
     }
 
     // KzgError tests
-    #[test]
-    fn test_kzg_error_commit() {
-        let msg = String::from("test commit error");
-        let error = KzgError::CommitError(msg.clone());
-        assert_eq!(format!("{}", error), format!("Commitment error: {}", msg));
-    }
-
     #[test]
     fn test_kzg_error_serialization() {
         let msg = String::from("test serialization error");
-        let error = KzgError::SerializationError(msg.clone());
+        let error = KzgError::Serialization(msg.clone());
         assert_eq!(
             format!("{}", error),
             format!("Serialization error: {}", msg)
@@ -113,28 +426,79 @@ mod tests {
     }
 
     #[test]
-    fn test_kzg_error_fft() {
-        let msg = String::from("test fft error");
-        let error = KzgError::FftError(msg.clone());
-        assert_eq!(format!("{}", error), format!("FFT error: {}", msg));
+    fn test_kzg_error_domain_size() {
+        let error = KzgError::InvalidDomainSize(12);
+        assert_eq!(format!("{}", error), "FFT error: no domain of size 12");
     }
 
     #[test]
-    fn test_kzg_error_generic() {
-        let msg = String::from("test generic error");
-        let error = KzgError::GenericError(msg.clone());
-        assert_eq!(format!("{}", error), format!("Generic error: {}", msg));
+    fn test_kzg_error_g2_power_unavailable() {
+        let error = KzgError::G2PowerUnavailable(16);
+        assert_eq!(
+            format!("{}", error),
+            "G2 error: tau^16 is not in the loaded g2 points"
+        );
     }
 
     #[test]
     fn test_kzg_error_equality() {
-        let error1 = KzgError::CommitError(String::from("error"));
-        let error2 = KzgError::CommitError(String::from("error"));
-        let error3 = KzgError::SerializationError(String::from("different error"));
+        let error1 = KzgError::IndexOutOfRange { index: 4, len: 4 };
+        let error2 = KzgError::IndexOutOfRange { index: 4, len: 4 };
+        let error3 = KzgError::Serialization(String::from("different error"));
         assert_eq!(error1, error2);
         assert_ne!(error1, error3);
     }
 
+    #[test]
+    fn test_kzg_error_sources() {
+        let error = KzgError::InvalidPoint {
+            index: 7,
+            source: PointDecodingError::NotOnCurve,
+        }
+        .in_file("g1.point");
+        assert_eq!(
+            format!("{}", error),
+            "g1.point: point 7: point is not on the curve"
+        );
+
+        // every level of the chain can be walked down to the decoding error
+        let point_error = error.source().unwrap();
+        assert_eq!(
+            format!("{}", point_error),
+            "point 7: point is not on the curve"
+        );
+        let decoding_error = point_error.source().unwrap();
+        assert_eq!(
+            decoding_error.downcast_ref::<PointDecodingError>(),
+            Some(&PointDecodingError::NotOnCurve)
+        );
+
+        let error = KzgError::from(BlobError::from(PolynomialError::EmptyElements));
+        assert_eq!(
+            error,
+            KzgError::Blob(BlobError::Polynomial(PolynomialError::EmptyElements))
+        );
+        let blob_error = error.source().unwrap();
+        assert_eq!(
+            blob_error.source().map(|err| err.to_string()),
+            Some("elements are empty".to_string())
+        );
+    }
+
+    #[test]
+    fn test_kzg_error_from_io() {
+        let io_error = io::Error::new(io::ErrorKind::NotFound, "no such file");
+        let error = KzgError::from(io_error);
+        assert_eq!(
+            error,
+            KzgError::Io {
+                kind: io::ErrorKind::NotFound,
+                message: "no such file".to_string()
+            }
+        );
+        assert_eq!(format!("{}", error), "io error: no such file");
+    }
+
     #[test]
     fn test_not_padded_error_display() {
         let error = BlobError::NotPaddedError;
@@ -161,9 +525,26 @@ mod tests {
     }
 
     #[test]
-    fn test_blob_generic_error() {
-        let error1 = BlobError::GenericError(String::from("error"));
-        let error3 = BlobError::GenericError(String::from("error"));
+    fn test_point_decoding_error_display() {
+        let error = PointDecodingError::InvalidLength {
+            expected: 32,
+            actual: 31,
+        };
+        assert_eq!(format!("{}", error), "expected 32 bytes, got 31");
+        assert_eq!(
+            format!("{}", PointDecodingError::InvalidCompressionFlag(0)),
+            "invalid compression flag: 0x00"
+        );
+        assert_eq!(
+            format!("{}", PointDecodingError::InvalidInfinityEncoding),
+            "point at infinity not coded properly"
+        );
+    }
+
+    #[test]
+    fn test_blob_polynomial_error() {
+        let error1 = BlobError::Polynomial(PolynomialError::EmptyElements);
+        let error3 = BlobError::from(PolynomialError::EmptyElements);
         let error2 = BlobError::NotPaddedError;
         assert_eq!(error1, error3);
         assert_ne!(error1, error2);
@@ -1,26 +1,37 @@
 use ark_bn254::{Fq, Fq2, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
 use ark_ec::AffineRepr;
-use ark_ff::{sbb, BigInt, BigInteger, Field, LegendreSymbol, PrimeField};
+use ark_ff::{sbb, BigInt, BigInteger, Field, PrimeField};
 use ark_std::{str::FromStr, vec::Vec, One, Zero};
 use std::cmp;
 
 use crate::{
     arith,
-    consts::{BYTES_PER_FIELD_ELEMENT, SIZE_OF_G1_AFFINE_COMPRESSED, SIZE_OF_G2_AFFINE_COMPRESSED},
+    consts::{
+        BYTES_PER_FIELD_ELEMENT, COMPRESSED_FLAG_MASK, COMPRESSED_INFINITY, COMPRESSED_LARGEST,
+        COMPRESSED_SMALLEST, SIZE_OF_G1_AFFINE_COMPRESSED, SIZE_OF_G1_AFFINE_UNCOMPRESSED,
+        SIZE_OF_G2_AFFINE_COMPRESSED, SIZE_OF_G2_AFFINE_UNCOMPRESSED, UNCOMPRESSED,
+    },
+    errors::{PointDecodingError, PolynomialError},
 };
 
+#[allow(clippy::ptr_arg)]
 pub fn blob_to_polynomial(blob: &Vec<u8>) -> Vec<Fr> {
-    to_fr_array(&blob)
+    to_fr_array(blob)
 }
 
-pub fn set_bytes_canonical_manual(data: &[u8]) -> Fr {
-    let mut arrays: [u64; 4] = Default::default(); // Initialize an array of four [u8; 8] arrays
-
-    for (i, chunk) in data.chunks(8).enumerate() {
-        arrays[i] = u64::from_be_bytes(chunk.try_into().expect("Slice with incorrect length"));
+/// Reads 32 big-endian bytes as a field element, which must be below the modulus.
+pub fn set_bytes_canonical_manual(data: &[u8]) -> Result<Fr, PolynomialError> {
+    if data.len() != BYTES_PER_FIELD_ELEMENT {
+        return Err(PolynomialError::InvalidLength {
+            expected: BYTES_PER_FIELD_ELEMENT,
+            actual: data.len(),
+        });
+    }
+    let mut limbs = [0u64; 4];
+    for (i, byte) in data.iter().enumerate() {
+        limbs[3 - i / 8] |= (*byte as u64) << (8 * (7 - i % 8));
     }
-    arrays.reverse();
-    Fr::from_bigint(BigInt::new(arrays)).unwrap()
+    Fr::from_bigint(BigInt::new(limbs)).ok_or(PolynomialError::NonCanonicalFieldElement)
 }
 
 // Functions being used
@@ -81,7 +92,7 @@ pub fn remove_empty_byte_from_padded_bytes(data: &[u8]) -> Vec<u8> {
 }
 
 pub fn set_bytes_canonical(data: &[u8]) -> Fr {
-    return Fr::from_be_bytes_mod_order(&data);
+    Fr::from_be_bytes_mod_order(data)
 }
 
 fn get_num_element(data_len: usize, symbol_size: usize) -> usize {
@@ -92,15 +103,15 @@ pub fn to_fr_array(data: &[u8]) -> Vec<Fr> {
     let num_ele = get_num_element(data.len(), BYTES_PER_FIELD_ELEMENT);
     let mut eles = vec![Fr::zero(); num_ele]; // Initialize with zero elements
 
-    for i in 0..num_ele {
+    for (i, ele) in eles.iter_mut().enumerate() {
         let start = i * BYTES_PER_FIELD_ELEMENT;
         let end = (i + 1) * BYTES_PER_FIELD_ELEMENT;
         if end > data.len() {
             let mut padded = vec![0u8; BYTES_PER_FIELD_ELEMENT];
             padded[..data.len() - start].copy_from_slice(&data[start..]);
-            eles[i] = set_bytes_canonical(&padded);
+            *ele = set_bytes_canonical(&padded);
         } else {
-            eles[i] = set_bytes_canonical(&data[start..end]);
+            *ele = set_bytes_canonical(&data[start..end]);
         }
     }
     eles
@@ -111,8 +122,8 @@ pub fn to_byte_array(data_fr: &[Fr], max_data_size: usize) -> Vec<u8> {
     let data_size = cmp::min(n * BYTES_PER_FIELD_ELEMENT, max_data_size);
     let mut data = vec![0u8; data_size];
 
-    for i in 0..n {
-        let v: Vec<u8> = data_fr[i].into_bigint().to_bytes_be();
+    for (i, fr) in data_fr.iter().enumerate() {
+        let v: Vec<u8> = fr.into_bigint().to_bytes_be();
 
         let start = i * BYTES_PER_FIELD_ELEMENT;
         let end = (i + 1) * BYTES_PER_FIELD_ELEMENT;
@@ -134,24 +145,20 @@ pub fn is_zeroed(first_byte: u8, buf: Vec<u8>) -> bool {
         return false;
     }
 
-    for i in 0..buf.len() {
-        if buf[i] != 0 {
-            return false;
-        }
-    }
-    true
+    buf.iter().all(|&b| b == 0)
 }
 
-pub fn str_vec_to_fr_vec(input: Vec<&str>) -> Result<Vec<Fr>, &str> {
+pub fn str_vec_to_fr_vec(input: Vec<&str>) -> Result<Vec<Fr>, PolynomialError> {
     let mut output: Vec<Fr> = Vec::<Fr>::with_capacity(input.len());
 
-    for i in 0..input.len() {
-        if input[i] == "-1" {
+    for element in input {
+        if element == "-1" {
             let mut test = Fr::one();
             test.neg_in_place();
             output.push(test);
         } else {
-            let fr_data = Fr::from_str(input[i]).expect("could not load string to Fr");
+            let fr_data =
+                Fr::from_str(element).map_err(|_| PolynomialError::SerializationFromStringError)?;
             output.push(fr_data);
         }
     }
@@ -188,35 +195,38 @@ pub fn lexicographically_largest(z: &Fq) -> bool {
     borrow == 0
 }
 
-pub fn read_g2_point_from_bytes_be(g2_bytes_be: &Vec<u8>) -> Result<G2Affine, &str> {
-    if g2_bytes_be.len() != SIZE_OF_G2_AFFINE_COMPRESSED {
-        return Err("not enough bytes for g2 point");
+pub fn read_g2_point_from_bytes_be(g2_bytes_be: &[u8]) -> Result<G2Affine, PointDecodingError> {
+    let point = read_g2_point_from_bytes_be_unchecked(g2_bytes_be)?;
+    if !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(PointDecodingError::NotInSubgroup);
     }
+    Ok(point)
+}
 
-    let m_mask: u8 = 0b11 << 6;
-    let m_compressed_infinity: u8 = 0b01 << 6;
-    let m_compressed_smallest: u8 = 0b10 << 6;
-    let m_compressed_largest: u8 = 0b11 << 6;
-
-    let m_data = g2_bytes_be[0] & m_mask;
+/// Decompresses a g2 point without the subgroup check, which dominates the cost of
+/// decoding. Only use this for points coming from a trusted source such as the SRS.
+pub fn read_g2_point_from_bytes_be_unchecked(
+    g2_bytes_be: &[u8],
+) -> Result<G2Affine, PointDecodingError> {
+    if g2_bytes_be.len() != SIZE_OF_G2_AFFINE_COMPRESSED {
+        return Err(PointDecodingError::InvalidLength {
+            expected: SIZE_OF_G2_AFFINE_COMPRESSED,
+            actual: g2_bytes_be.len(),
+        });
+    }
 
-    if m_data == m_compressed_infinity {
-        if !is_zeroed(
-            g2_bytes_be[0] & !m_mask,
-            g2_bytes_be[1..SIZE_OF_G2_AFFINE_COMPRESSED].to_vec(),
-        ) {
-            return Err("point at infinity not coded properly for g2");
-        }
+    let m_data = read_compression_flag(g2_bytes_be)?;
+    if m_data == COMPRESSED_INFINITY {
         return Ok(G2Affine::zero());
     }
 
     let mut x_bytes = [0u8; SIZE_OF_G2_AFFINE_COMPRESSED];
     x_bytes.copy_from_slice(g2_bytes_be);
-    x_bytes[0] &= !m_mask;
+    x_bytes[0] &= !COMPRESSED_FLAG_MASK;
     let half_size = SIZE_OF_G2_AFFINE_COMPRESSED / 2;
 
-    let c1 = Fq::from_be_bytes_mod_order(&x_bytes[..half_size]);
-    let c0 = Fq::from_be_bytes_mod_order(&x_bytes[half_size..]);
+    let c1 = read_fq_canonical(&x_bytes[..half_size])?;
+    let c0 = read_fq_canonical(&x_bytes[half_size..])?;
     let x = Fq2::new(c0, c1);
     let y_squared = x * x * x;
 
@@ -224,99 +234,283 @@ pub fn read_g2_point_from_bytes_be(g2_bytes_be: &Vec<u8>) -> Result<G2Affine, &s
     let twist_curve_coeff = get_b_twist_curve_coeff();
 
     let added_result = y_squared + twist_curve_coeff;
-    if added_result.legendre() == LegendreSymbol::QuadraticNonResidue {
-        return Err("invalid compressed coordinate: square root doesn't exist");
-    }
-
-    let mut y_sqrt = added_result.sqrt().ok_or("no square root found").unwrap();
-
-    #[allow(dead_code)]
-    let mut lexicographical_check_result = false;
+    let mut y_sqrt = added_result
+        .sqrt()
+        .ok_or(PointDecodingError::NoSquareRoot)?;
 
-    if y_sqrt.c1.0.is_zero() {
-        lexicographical_check_result = lexicographically_largest(&y_sqrt.c0);
+    let lexicographical_check_result = if y_sqrt.c1.0.is_zero() {
+        lexicographically_largest(&y_sqrt.c0)
     } else {
-        lexicographical_check_result = lexicographically_largest(&y_sqrt.c1);
-    }
+        lexicographically_largest(&y_sqrt.c1)
+    };
 
     if lexicographical_check_result {
-        if m_data == m_compressed_smallest {
-            y_sqrt.neg_in_place();
-        }
-    } else {
-        if m_data == m_compressed_largest {
+        if m_data == COMPRESSED_SMALLEST {
             y_sqrt.neg_in_place();
         }
+    } else if m_data == COMPRESSED_LARGEST {
+        y_sqrt.neg_in_place();
     }
 
     let point = G2Affine::new_unchecked(x, y_sqrt);
-    if !point.is_in_correct_subgroup_assuming_on_curve()
-        && is_on_curve_g2(&G2Projective::from(point))
-    {
-        return Err("point couldn't be created");
+    if !is_on_curve_g2(&G2Projective::from(point)) {
+        return Err(PointDecodingError::NotOnCurve);
     }
     Ok(point)
 }
 
-pub fn read_g1_point_from_bytes_be(g1_bytes_be: &Vec<u8>) -> Result<G1Affine, &str> {
-    if g1_bytes_be.len() != SIZE_OF_G1_AFFINE_COMPRESSED {
-        return Err("not enough bytes for g1 point");
+pub fn read_g1_point_from_bytes_be(g1_bytes_be: &[u8]) -> Result<G1Affine, PointDecodingError> {
+    let point = read_g1_point_from_bytes_be_unchecked(g1_bytes_be)?;
+    if !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(PointDecodingError::NotInSubgroup);
     }
+    Ok(point)
+}
 
-    let m_mask: u8 = 0b11 << 6;
-    let m_compressed_infinity: u8 = 0b01 << 6;
-    let m_compressed_smallest: u8 = 0b10 << 6;
-    let m_compressed_largest: u8 = 0b11 << 6;
-
-    let m_data = g1_bytes_be[0] & m_mask;
+/// Decompresses a g1 point without the subgroup check, see
+/// `read_g2_point_from_bytes_be_unchecked`.
+pub fn read_g1_point_from_bytes_be_unchecked(
+    g1_bytes_be: &[u8],
+) -> Result<G1Affine, PointDecodingError> {
+    if g1_bytes_be.len() != SIZE_OF_G1_AFFINE_COMPRESSED {
+        return Err(PointDecodingError::InvalidLength {
+            expected: SIZE_OF_G1_AFFINE_COMPRESSED,
+            actual: g1_bytes_be.len(),
+        });
+    }
 
-    if m_data == m_compressed_infinity {
-        if !is_zeroed(g1_bytes_be[0] & !m_mask, g1_bytes_be[1..32].to_vec()) {
-            return Err("point at infinity not coded properly for g1");
-        }
+    let m_data = read_compression_flag(g1_bytes_be)?;
+    if m_data == COMPRESSED_INFINITY {
         return Ok(G1Affine::zero());
     }
 
     let mut x_bytes = [0u8; SIZE_OF_G1_AFFINE_COMPRESSED];
     x_bytes.copy_from_slice(g1_bytes_be);
-    x_bytes[0] &= !m_mask;
-    let x = Fq::from_be_bytes_mod_order(&x_bytes);
+    x_bytes[0] &= !COMPRESSED_FLAG_MASK;
+    let x = read_fq_canonical(&x_bytes)?;
     let y_squared = x * x * x + Fq::from(3);
-    let mut y_sqrt = y_squared.sqrt().ok_or("no item1").unwrap();
+    let mut y_sqrt = y_squared.sqrt().ok_or(PointDecodingError::NoSquareRoot)?;
 
     if lexicographically_largest(&y_sqrt) {
-        if m_data == m_compressed_smallest {
-            y_sqrt.neg_in_place();
-        }
-    } else {
-        if m_data == m_compressed_largest {
+        if m_data == COMPRESSED_SMALLEST {
             y_sqrt.neg_in_place();
         }
+    } else if m_data == COMPRESSED_LARGEST {
+        y_sqrt.neg_in_place();
     }
+
     let point = G1Affine::new_unchecked(x, y_sqrt);
-    if !point.is_in_correct_subgroup_assuming_on_curve()
-        && is_on_curve_g1(&G1Projective::from(point))
-    {
-        return Err("point couldn't be created");
+    if !is_on_curve_g1(&G1Projective::from(point)) {
+        return Err(PointDecodingError::NotOnCurve);
     }
     Ok(point)
 }
 
+/// Compresses a g1 point into the 32 byte big-endian format produced by gnark's
+/// `Bytes()`, the inverse of `read_g1_point_from_bytes_be`.
+pub fn write_g1_point_to_bytes_be(point: &G1Affine) -> [u8; SIZE_OF_G1_AFFINE_COMPRESSED] {
+    let mut bytes = [0u8; SIZE_OF_G1_AFFINE_COMPRESSED];
+    if point.infinity {
+        bytes[0] = COMPRESSED_INFINITY;
+        return bytes;
+    }
+
+    bytes.copy_from_slice(&point.x.into_bigint().to_bytes_be());
+    bytes[0] |= if lexicographically_largest(&point.y) {
+        COMPRESSED_LARGEST
+    } else {
+        COMPRESSED_SMALLEST
+    };
+    bytes
+}
+
+/// Compresses a g2 point into the 64 byte big-endian format produced by gnark's
+/// `Bytes()`: x.c1 followed by x.c0, the flags live in the first byte.
+pub fn write_g2_point_to_bytes_be(point: &G2Affine) -> [u8; SIZE_OF_G2_AFFINE_COMPRESSED] {
+    let mut bytes = [0u8; SIZE_OF_G2_AFFINE_COMPRESSED];
+    if point.infinity {
+        bytes[0] = COMPRESSED_INFINITY;
+        return bytes;
+    }
+
+    let half_size = SIZE_OF_G2_AFFINE_COMPRESSED / 2;
+    bytes[..half_size].copy_from_slice(&point.x.c1.into_bigint().to_bytes_be());
+    bytes[half_size..].copy_from_slice(&point.x.c0.into_bigint().to_bytes_be());
+
+    let largest = if point.y.c1.0.is_zero() {
+        lexicographically_largest(&point.y.c0)
+    } else {
+        lexicographically_largest(&point.y.c1)
+    };
+    bytes[0] |= if largest {
+        COMPRESSED_LARGEST
+    } else {
+        COMPRESSED_SMALLEST
+    };
+    bytes
+}
+
+/// Reads a g1 point in gnark's 64 byte uncompressed `RawBytes()` format: x followed
+/// by y, both big-endian. The point at infinity is all zeros.
+pub fn read_g1_point_uncompressed_be(g1_bytes_be: &[u8]) -> Result<G1Affine, PointDecodingError> {
+    if g1_bytes_be.len() != SIZE_OF_G1_AFFINE_UNCOMPRESSED {
+        return Err(PointDecodingError::InvalidLength {
+            expected: SIZE_OF_G1_AFFINE_UNCOMPRESSED,
+            actual: g1_bytes_be.len(),
+        });
+    }
+    read_uncompressed_flag(g1_bytes_be)?;
+    if g1_bytes_be.iter().all(|byte| *byte == 0) {
+        return Ok(G1Affine::zero());
+    }
+
+    let half_size = SIZE_OF_G1_AFFINE_UNCOMPRESSED / 2;
+    let x = read_fq_canonical(&g1_bytes_be[..half_size])?;
+    let y = read_fq_canonical(&g1_bytes_be[half_size..])?;
+    let point = G1Affine::new_unchecked(x, y);
+    validate_g1_point(&point)?;
+    Ok(point)
+}
+
+/// Writes a g1 point in gnark's 64 byte uncompressed `RawBytes()` format.
+pub fn write_g1_point_uncompressed_be(point: &G1Affine) -> [u8; SIZE_OF_G1_AFFINE_UNCOMPRESSED] {
+    let mut bytes = [0u8; SIZE_OF_G1_AFFINE_UNCOMPRESSED];
+    if point.infinity {
+        return bytes;
+    }
+
+    let half_size = SIZE_OF_G1_AFFINE_UNCOMPRESSED / 2;
+    bytes[..half_size].copy_from_slice(&point.x.into_bigint().to_bytes_be());
+    bytes[half_size..].copy_from_slice(&point.y.into_bigint().to_bytes_be());
+    bytes
+}
+
+/// Reads a g2 point in gnark's 128 byte uncompressed `RawBytes()` format:
+/// x.c1, x.c0, y.c1, y.c0, all big-endian. The point at infinity is all zeros.
+pub fn read_g2_point_uncompressed_be(g2_bytes_be: &[u8]) -> Result<G2Affine, PointDecodingError> {
+    if g2_bytes_be.len() != SIZE_OF_G2_AFFINE_UNCOMPRESSED {
+        return Err(PointDecodingError::InvalidLength {
+            expected: SIZE_OF_G2_AFFINE_UNCOMPRESSED,
+            actual: g2_bytes_be.len(),
+        });
+    }
+    read_uncompressed_flag(g2_bytes_be)?;
+    if g2_bytes_be.iter().all(|byte| *byte == 0) {
+        return Ok(G2Affine::zero());
+    }
+
+    let coordinate = |i: usize| {
+        read_fq_canonical(
+            &g2_bytes_be[i * BYTES_PER_FIELD_ELEMENT..(i + 1) * BYTES_PER_FIELD_ELEMENT],
+        )
+    };
+    let (x_c1, x_c0, y_c1, y_c0) = (
+        coordinate(0)?,
+        coordinate(1)?,
+        coordinate(2)?,
+        coordinate(3)?,
+    );
+    let point = G2Affine::new_unchecked(Fq2::new(x_c0, x_c1), Fq2::new(y_c0, y_c1));
+    validate_g2_point(&point)?;
+    Ok(point)
+}
+
+/// Writes a g2 point in gnark's 128 byte uncompressed `RawBytes()` format.
+pub fn write_g2_point_uncompressed_be(point: &G2Affine) -> [u8; SIZE_OF_G2_AFFINE_UNCOMPRESSED] {
+    let mut bytes = [0u8; SIZE_OF_G2_AFFINE_UNCOMPRESSED];
+    if point.infinity {
+        return bytes;
+    }
+
+    let coordinates = [point.x.c1, point.x.c0, point.y.c1, point.y.c0];
+    for (chunk, coordinate) in bytes
+        .chunks_mut(BYTES_PER_FIELD_ELEMENT)
+        .zip(coordinates.iter())
+    {
+        chunk.copy_from_slice(&coordinate.into_bigint().to_bytes_be());
+    }
+    bytes
+}
+
+/// Checks a g1 point built from untrusted coordinates is on the curve and in the subgroup.
+pub fn validate_g1_point(point: &G1Affine) -> Result<(), PointDecodingError> {
+    if point.infinity {
+        return Ok(());
+    }
+    if !is_on_curve_g1(&G1Projective::from(*point)) {
+        return Err(PointDecodingError::NotOnCurve);
+    }
+    if !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(PointDecodingError::NotInSubgroup);
+    }
+    Ok(())
+}
+
+/// Checks a g2 point built from untrusted coordinates is on the curve and in the subgroup.
+pub fn validate_g2_point(point: &G2Affine) -> Result<(), PointDecodingError> {
+    if point.infinity {
+        return Ok(());
+    }
+    if !is_on_curve_g2(&G2Projective::from(*point)) {
+        return Err(PointDecodingError::NotOnCurve);
+    }
+    if !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(PointDecodingError::NotInSubgroup);
+    }
+    Ok(())
+}
+
+/// Returns the gnark compression flag of a compressed point. The point at infinity
+/// must have every other bit cleared, and the uncompressed flag is rejected.
+fn read_compression_flag(bytes: &[u8]) -> Result<u8, PointDecodingError> {
+    let m_data = bytes[0] & COMPRESSED_FLAG_MASK;
+    match m_data {
+        COMPRESSED_INFINITY => {
+            if !is_zeroed(bytes[0] & !COMPRESSED_FLAG_MASK, bytes[1..].to_vec()) {
+                return Err(PointDecodingError::InvalidInfinityEncoding);
+            }
+            Ok(m_data)
+        }
+        COMPRESSED_SMALLEST | COMPRESSED_LARGEST => Ok(m_data),
+        _ => Err(PointDecodingError::InvalidCompressionFlag(m_data)),
+    }
+}
+
+/// Checks the gnark flag of an uncompressed point. gnark has no uncompressed
+/// infinity flag: it reads `0b01` as a 32 byte compressed infinity, which would
+/// misalign a stream of uncompressed points, so every flag but `0b00` is rejected.
+fn read_uncompressed_flag(bytes: &[u8]) -> Result<(), PointDecodingError> {
+    match bytes[0] & COMPRESSED_FLAG_MASK {
+        UNCOMPRESSED => Ok(()),
+        m_data => Err(PointDecodingError::InvalidCompressionFlag(m_data)),
+    }
+}
+
+/// Reads a big-endian base field element, rejecting values at or above the modulus
+/// instead of silently reducing them.
+fn read_fq_canonical(bytes: &[u8]) -> Result<Fq, PointDecodingError> {
+    let fq = Fq::from_be_bytes_mod_order(bytes);
+    if fq.into_bigint().to_bytes_be() != bytes {
+        return Err(PointDecodingError::NonCanonicalCoordinate);
+    }
+    Ok(fq)
+}
+
 fn get_b_twist_curve_coeff() -> Fq2 {
     let twist_c0 = Fq::from(9);
     let twist_c1 = Fq::from(1);
 
     // this is bTwistCurveCoeff
-    let mut twist_curve_coeff = Fq2::new(twist_c0, twist_c1);
-    twist_curve_coeff = *twist_curve_coeff.inverse_in_place().unwrap();
+    // 9 + u is not zero, so the inverse always exists
+    let mut twist_curve_coeff = Fq2::new(twist_c0, twist_c1).inverse().unwrap_or_default();
 
-    twist_curve_coeff.c0 = twist_curve_coeff.c0 * Fq::from(3);
-    twist_curve_coeff.c1 = twist_curve_coeff.c1 * Fq::from(3);
+    twist_curve_coeff.c0 *= Fq::from(3);
+    twist_curve_coeff.c1 *= Fq::from(3);
     twist_curve_coeff
 }
 
 pub fn is_on_curve_g1(g1: &G1Projective) -> bool {
-    let b_curve_coeff: Fq = Fq::from_str("3").unwrap();
+    let b_curve_coeff = Fq::from(3);
 
     let mut left = g1.y;
     left.square_in_place();
@@ -394,7 +588,7 @@ fn test_blob_to_polynomial() {
 
     let file = File::open("src/test-files/blobs.txt").unwrap();
     let mut reader = BufReader::new(file);
-    let mut buffer = vec![0u8; SIZE_OF_G1_AFFINE_COMPRESSED];
+    let mut buffer = [0u8; SIZE_OF_G1_AFFINE_COMPRESSED];
     let mut read_fr_from_bytes: Vec<Fr> = vec![];
     let mut fr_from_str_vec: Vec<Fr> = vec![];
 
@@ -442,13 +636,13 @@ fn test_to_fr_array() {
         .as_slice(),
     );
     let data_fr = to_fr_array(&converted);
-    let result = to_byte_array(&data_fr, converted.len().try_into().unwrap());
+    let result = to_byte_array(&data_fr, converted.len());
     assert_eq!(converted, result, "should be deserialized properly");
 
     let ga_converted = convert_by_padding_empty_byte(GETTYSBURG_ADDRESS_BYTES);
     let ga_converted_fr = to_fr_array(&ga_converted);
     assert_eq!(
-        to_byte_array(&ga_converted_fr, ga_converted.len().try_into().unwrap()),
+        to_byte_array(&ga_converted_fr, ga_converted.len()),
         ga_converted
     );
 }
@@ -475,8 +669,19 @@ fn test_set_canonical_bytes() {
         0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
         25, 26, 27, 28, 29, 30, 31,
     ];
-    let fr_element = set_bytes_canonical_manual(&data);
+    let fr_element = set_bytes_canonical_manual(&data).unwrap();
     assert_eq!(fr_element, set_bytes_canonical(&data), "needs to be equal");
+    assert_eq!(
+        set_bytes_canonical_manual(&data[1..]),
+        Err(PolynomialError::InvalidLength {
+            expected: 32,
+            actual: 31
+        })
+    );
+    assert_eq!(
+        set_bytes_canonical_manual(&[0xff; BYTES_PER_FIELD_ELEMENT]),
+        Err(PolynomialError::NonCanonicalFieldElement)
+    );
 }
 
 #[test]
@@ -649,6 +854,249 @@ mod tests {
         // Case where the buffer is empty and the first byte is non-zero
         let binding = vec![];
         let result = read_g2_point_from_bytes_be(&binding);
-        assert_eq!(result, Err("not enough bytes for g2 point"));
+        assert_eq!(
+            result,
+            Err(PointDecodingError::InvalidLength {
+                expected: 64,
+                actual: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_write_g1_points_round_trip() {
+        let g1_bytes = std::fs::read("src/test-files/g1.point").unwrap();
+        for bytes in g1_bytes.chunks(SIZE_OF_G1_AFFINE_COMPRESSED) {
+            let point = read_g1_point_from_bytes_be(bytes).unwrap();
+            assert_eq!(write_g1_point_to_bytes_be(&point).as_slice(), bytes);
+        }
+
+        let infinity = write_g1_point_to_bytes_be(&G1Affine::zero());
+        assert_eq!(infinity[0], COMPRESSED_INFINITY);
+        assert!(is_zeroed(0, infinity[1..].to_vec()));
+        assert_eq!(read_g1_point_from_bytes_be(&infinity), Ok(G1Affine::zero()));
+
+        let generator = G1Affine::generator();
+        let negated = -generator;
+        assert_ne!(
+            write_g1_point_to_bytes_be(&generator),
+            write_g1_point_to_bytes_be(&negated)
+        );
+        assert_eq!(
+            read_g1_point_from_bytes_be(&write_g1_point_to_bytes_be(&negated)),
+            Ok(negated)
+        );
+    }
+
+    #[test]
+    fn test_write_g2_points_round_trip() {
+        let g2_bytes = std::fs::read("src/test-files/g2.point").unwrap();
+        for bytes in g2_bytes.chunks(SIZE_OF_G2_AFFINE_COMPRESSED) {
+            let point = read_g2_point_from_bytes_be_unchecked(bytes).unwrap();
+            assert_eq!(write_g2_point_to_bytes_be(&point).as_slice(), bytes);
+        }
+
+        let infinity = write_g2_point_to_bytes_be(&G2Affine::zero());
+        assert_eq!(infinity[0], COMPRESSED_INFINITY);
+        assert!(is_zeroed(0, infinity[1..].to_vec()));
+        assert_eq!(read_g2_point_from_bytes_be(&infinity), Ok(G2Affine::zero()));
+
+        let negated = -G2Affine::generator();
+        assert_eq!(
+            read_g2_point_from_bytes_be(&write_g2_point_to_bytes_be(&negated)),
+            Ok(negated)
+        );
+    }
+
+    /// big-endian bytes of the base field modulus, the smallest non-canonical coordinate
+    fn fq_modulus_be() -> Vec<u8> {
+        <Fq as PrimeField>::MODULUS.to_bytes_be()
+    }
+
+    /// first x = start, start + 1, ... for which x^3 + b has (or lacks) a square root
+    fn find_g1_x(start: u64, has_root: bool) -> Vec<u8> {
+        (start..)
+            .map(Fq::from)
+            .find(|x| (*x * x * x + Fq::from(3)).sqrt().is_some() == has_root)
+            .unwrap()
+            .into_bigint()
+            .to_bytes_be()
+    }
+
+    fn find_g2_x(start: u64, has_root: bool) -> Vec<u8> {
+        let x = (start..)
+            .map(|c0| Fq2::new(Fq::from(c0), Fq::one()))
+            .find(|x| (*x * x * x + get_b_twist_curve_coeff()).sqrt().is_some() == has_root)
+            .unwrap();
+        let mut bytes = x.c1.into_bigint().to_bytes_be();
+        bytes.extend(x.c0.into_bigint().to_bytes_be());
+        bytes
+    }
+
+    #[test]
+    fn test_read_g1_point_malformed_encodings() {
+        let cases: Vec<(Vec<u8>, PointDecodingError)> = vec![
+            (
+                vec![0x80; 31],
+                PointDecodingError::InvalidLength {
+                    expected: 32,
+                    actual: 31,
+                },
+            ),
+            (
+                vec![0x80; 33],
+                PointDecodingError::InvalidLength {
+                    expected: 32,
+                    actual: 33,
+                },
+            ),
+            (
+                {
+                    let mut bytes = fq_modulus_be();
+                    bytes[0] |= COMPRESSED_SMALLEST;
+                    bytes
+                },
+                PointDecodingError::NonCanonicalCoordinate,
+            ),
+            (
+                {
+                    let mut bytes = vec![0xff; 32];
+                    bytes[0] = COMPRESSED_LARGEST | 0x3f;
+                    bytes
+                },
+                PointDecodingError::NonCanonicalCoordinate,
+            ),
+            (
+                {
+                    let mut bytes = find_g1_x(1, false);
+                    bytes[0] |= COMPRESSED_SMALLEST;
+                    bytes
+                },
+                PointDecodingError::NoSquareRoot,
+            ),
+            (
+                {
+                    let mut bytes = vec![0; 32];
+                    bytes[0] = COMPRESSED_INFINITY;
+                    bytes[31] = 1;
+                    bytes
+                },
+                PointDecodingError::InvalidInfinityEncoding,
+            ),
+            (
+                {
+                    let mut bytes = vec![0; 32];
+                    bytes[0] = COMPRESSED_INFINITY | 1;
+                    bytes
+                },
+                PointDecodingError::InvalidInfinityEncoding,
+            ),
+            (
+                find_g1_x(1, true),
+                PointDecodingError::InvalidCompressionFlag(0),
+            ),
+        ];
+
+        for (bytes, expected) in cases {
+            assert_eq!(read_g1_point_from_bytes_be(&bytes), Err(expected.clone()));
+            assert_eq!(read_g1_point_from_bytes_be_unchecked(&bytes), Err(expected));
+        }
+
+        let mut infinity = vec![0; 32];
+        infinity[0] = COMPRESSED_INFINITY;
+        assert_eq!(read_g1_point_from_bytes_be(&infinity), Ok(G1Affine::zero()));
+
+        let mut valid = find_g1_x(1, true);
+        valid[0] |= COMPRESSED_LARGEST;
+        let point = read_g1_point_from_bytes_be(&valid).unwrap();
+        assert_eq!(validate_g1_point(&point), Ok(()));
+
+        let mut off_curve = point;
+        off_curve.y += Fq::one();
+        assert_eq!(
+            validate_g1_point(&off_curve),
+            Err(PointDecodingError::NotOnCurve)
+        );
+        // g1 has cofactor one, so every point on the curve is in the subgroup and
+        // the NotInSubgroup branch can only be reached for g2.
+    }
+
+    #[test]
+    fn test_read_g2_point_malformed_encodings() {
+        let cases: Vec<(Vec<u8>, PointDecodingError)> = vec![
+            (
+                vec![0x80; 63],
+                PointDecodingError::InvalidLength {
+                    expected: 64,
+                    actual: 63,
+                },
+            ),
+            (
+                {
+                    let mut bytes = fq_modulus_be();
+                    bytes.extend(vec![0; 32]);
+                    bytes[0] |= COMPRESSED_SMALLEST;
+                    bytes
+                },
+                PointDecodingError::NonCanonicalCoordinate,
+            ),
+            (
+                {
+                    let mut bytes = vec![0; 32];
+                    bytes.extend(fq_modulus_be());
+                    bytes[0] |= COMPRESSED_LARGEST;
+                    bytes
+                },
+                PointDecodingError::NonCanonicalCoordinate,
+            ),
+            (
+                {
+                    let mut bytes = find_g2_x(1, false);
+                    bytes[0] |= COMPRESSED_SMALLEST;
+                    bytes
+                },
+                PointDecodingError::NoSquareRoot,
+            ),
+            (
+                {
+                    let mut bytes = vec![0; 64];
+                    bytes[0] = COMPRESSED_INFINITY;
+                    bytes[40] = 1;
+                    bytes
+                },
+                PointDecodingError::InvalidInfinityEncoding,
+            ),
+            (
+                find_g2_x(1, true),
+                PointDecodingError::InvalidCompressionFlag(0),
+            ),
+        ];
+
+        for (bytes, expected) in cases {
+            assert_eq!(read_g2_point_from_bytes_be(&bytes), Err(expected.clone()));
+            assert_eq!(read_g2_point_from_bytes_be_unchecked(&bytes), Err(expected));
+        }
+
+        // an x with a square root gives a point on the twist, which is almost never
+        // in the prime order subgroup
+        let mut not_in_subgroup = find_g2_x(1, true);
+        not_in_subgroup[0] |= COMPRESSED_SMALLEST;
+        assert_eq!(
+            read_g2_point_from_bytes_be(&not_in_subgroup),
+            Err(PointDecodingError::NotInSubgroup)
+        );
+        let point = read_g2_point_from_bytes_be_unchecked(&not_in_subgroup).unwrap();
+        assert_eq!(
+            validate_g2_point(&point),
+            Err(PointDecodingError::NotInSubgroup)
+        );
+
+        let mut off_curve = G2Affine::generator();
+        off_curve.y += Fq2::one();
+        assert_eq!(
+            validate_g2_point(&off_curve),
+            Err(PointDecodingError::NotOnCurve)
+        );
+        assert_eq!(validate_g2_point(&G2Affine::generator()), Ok(()));
     }
 }
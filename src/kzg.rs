@@ -1,185 +1,345 @@
 use crate::blob::Blob;
-use crate::consts::BYTES_PER_FIELD_ELEMENT;
+use crate::consts::{COMMITMENT_EQUIVALENCE_DOMAIN, FIAT_SHAMIR_PROTOCOL_DOMAIN, KZG_BATCH_DOMAIN};
+use crate::domain::Domain;
+use crate::encoding::{self, EncodingParams, Frame};
 use crate::errors::KzgError;
-use crate::polynomial::Polynomial;
+use crate::helpers;
+use crate::lagrange::LagrangeCache;
+use crate::msm::PrecomputedBases;
+use crate::polynomial::{Polynomial, PolynomialForm};
+use crate::srs::{self, PointValidation};
+use crate::traits::PointCodec;
 use ark_bn254::g1::G1Affine;
-use ark_bn254::{Bn254, Fr, G1Projective, G2Affine};
+use ark_bn254::{Bn254, Fr, G1Projective, G2Affine, G2Projective};
 use ark_ec::pairing::Pairing;
 use ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
-use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-use ark_std::ops::{Div, Mul};
-use ark_std::str::FromStr;
-use ark_std::{One, Zero};
+use ark_ff::{batch_inversion, BigInteger, Field, PrimeField};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+    Write,
+};
+use ark_std::{One, UniformRand, Zero};
 use num_traits::ToPrimitive;
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::sync::Arc;
 
 #[derive(Debug, PartialEq, Clone, CanonicalSerialize, CanonicalDeserialize)]
 pub struct Kzg {
-    pub g1: Vec<G1Affine>,
+    pub(crate) g1: Vec<G1Affine>,
     pub g2: Vec<G2Affine>,
-    pub params: Params,
+    params: Option<EncodingParams>,
     pub srs_order: u64,
-    pub expanded_roots_of_unity: Vec<Fr>,
+    pub g2_layout: G2Layout,
+    /// derived from `g1` and trusted by `commit`, so only the crate writes to it
+    pub(crate) lagrange_cache: LagrangeCache,
 }
 
-#[derive(Debug, PartialEq, Clone, CanonicalSerialize, CanonicalDeserialize)]
-pub struct Params {
-    chunk_length: u64,
-    num_chunks: u64,
-    max_fft_width: u64,
-    completed_setup: bool,
+/// Which powers of tau the g2 points hold.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum G2Layout {
+    /// `g2[i] = [tau^i]_2`, as in the `g2.point` file
+    FullPowers,
+    /// `g2[k] = [tau^(2^k)]_2`, as in the `g2.point.powerOf2` file
+    PowersOfTwo,
+    /// only `g2[0] = [tau]_2`, enough to verify single openings
+    TauOnly,
+}
+
+impl CanonicalSerialize for G2Layout {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        (*self as u8).serialize_with_mode(writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        (*self as u8).serialized_size(compress)
+    }
+}
+
+impl Valid for G2Layout {
+    fn check(&self) -> Result<(), SerializationError> {
+        Ok(())
+    }
+}
+
+impl CanonicalDeserialize for G2Layout {
+    fn deserialize_with_mode<R: Read>(
+        reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        match u8::deserialize_with_mode(reader, compress, validate)? {
+            0 => Ok(G2Layout::FullPowers),
+            1 => Ok(G2Layout::PowersOfTwo),
+            2 => Ok(G2Layout::TauOnly),
+            _ => Err(SerializationError::InvalidData),
+        }
+    }
+}
+
+/// the bundled test srs, serialized back when `Kzg` held the roots of unity of
+/// its encoding and had no g2 layout
+#[derive(CanonicalDeserialize)]
+struct BundledSrs {
+    g1: Vec<G1Affine>,
+    g2: Vec<G2Affine>,
+    _params: BundledParams,
+    srs_order: u64,
+    _expanded_roots_of_unity: Vec<Fr>,
+}
+
+/// the encoding params as the bundled srs stores them, never set up
+#[derive(CanonicalDeserialize)]
+struct BundledParams {
+    _chunk_length: u64,
+    _num_chunks: u64,
+    _max_fft_width: u64,
+    _completed_setup: bool,
 }
 
-const KZG_MAINNET_BYTES: &[u8; 4_196_153] = include_bytes!("test-files/kzg_serialized_mainnet");
+/// Outcome of `verify_kzg_proof_batch_with_fallback`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum BatchVerification {
+    Valid,
+    /// indices of the openings that fail on their own
+    Invalid(Vec<usize>),
+}
 
 const KZG_TEST_BYTES: &[u8; 288_057] = include_bytes!("test-files/kzg_serialized_test");
 
 impl Kzg {
+    /// Loads the bundled test SRS. The mainnet SRS is far too large to embed in
+    /// the crate, load it with `setup_from_files` instead.
     pub fn setup(test: bool) -> Result<Self, KzgError> {
-        if test {
-            let kzg: Kzg =
-                CanonicalDeserialize::deserialize_compressed(KZG_TEST_BYTES.as_slice()).unwrap();
-            Ok(Self {
-                g1: kzg.g1,
-                g2: kzg.g2,
-                params: kzg.params,
-                srs_order: kzg.srs_order,
-                expanded_roots_of_unity: kzg.expanded_roots_of_unity,
-            })
+        if !test {
+            return Err(KzgError::MainnetSrsNotBundled);
+        }
+
+        let kzg: BundledSrs =
+            CanonicalDeserialize::deserialize_compressed(KZG_TEST_BYTES.as_slice())?;
+        Ok(Self {
+            g1: kzg.g1,
+            g2: kzg.g2,
+            params: None,
+            srs_order: kzg.srs_order,
+            g2_layout: G2Layout::FullPowers,
+            lagrange_cache: LagrangeCache::default(),
+        })
+    }
+
+    /// Loads the SRS at runtime from the compressed big-endian point files
+    /// distributed by EigenDA (`g1.point`, `g2.point`, `g2.point.powerOf2`).
+    ///
+    /// Only the first `srs_points_to_load` points of the G1 and G2 files are read.
+    /// Pass an empty `path_to_g2_points` to load the powers-of-two G2 file instead,
+    /// which is what operators running against the mainnet SRS should do.
+    /// Every point is fully validated, and the G1 and G2 points must share tau.
+    pub fn setup_from_files(
+        path_to_g1_points: &str,
+        path_to_g2_points: &str,
+        g2_power_of_2_path: &str,
+        srs_order: u64,
+        srs_points_to_load: u64,
+    ) -> Result<Self, KzgError> {
+        Self::setup_from_files_with_validation(
+            path_to_g1_points,
+            path_to_g2_points,
+            g2_power_of_2_path,
+            srs_order,
+            srs_points_to_load,
+            PointValidation::Full,
+        )
+    }
+
+    /// Same as `setup_from_files`, with control over how much checking is done on
+    /// the decoded points. `PointValidation::Trusted` skips the subgroup checks and
+    /// is much faster on large files.
+    pub fn setup_from_files_with_validation(
+        path_to_g1_points: &str,
+        path_to_g2_points: &str,
+        g2_power_of_2_path: &str,
+        srs_order: u64,
+        srs_points_to_load: u64,
+        validation: PointValidation,
+    ) -> Result<Self, KzgError> {
+        if srs_points_to_load > srs_order {
+            return Err(KzgError::SrsTooSmall {
+                required: srs_points_to_load,
+                available: srs_order,
+            });
+        }
+        let points_to_load = srs_points_to_load.to_usize().ok_or(KzgError::SrsTooSmall {
+            required: srs_points_to_load,
+            available: usize::MAX as u64,
+        })?;
+
+        let g1 = Self::read_points_from_file::<G1Affine>(
+            path_to_g1_points,
+            Some(points_to_load),
+            validation,
+        )?;
+
+        let (g2, g2_layout) = if !path_to_g2_points.is_empty() {
+            let g2 = Self::read_points_from_file::<G2Affine>(
+                path_to_g2_points,
+                Some(points_to_load),
+                validation,
+            )?;
+            (g2, G2Layout::FullPowers)
+        } else if !g2_power_of_2_path.is_empty() {
+            let g2 = Self::read_points_from_file::<G2Affine>(g2_power_of_2_path, None, validation)?;
+            (g2, G2Layout::PowersOfTwo)
         } else {
-            let kzg: Kzg =
-                CanonicalDeserialize::deserialize_compressed(KZG_MAINNET_BYTES.as_slice()).unwrap();
-            Ok(Self {
-                g1: kzg.g1,
-                g2: kzg.g2,
-                params: kzg.params,
-                srs_order: kzg.srs_order,
-                expanded_roots_of_unity: kzg.expanded_roots_of_unity,
-            })
+            return Err(KzgError::MissingG2Points);
+        };
+
+        let kzg = Self {
+            g1,
+            g2,
+            params: None,
+            srs_order,
+            g2_layout,
+            lagrange_cache: LagrangeCache::default(),
+        };
+        // e([tau]_1, [1]_2) = e([1]_1, [tau]_2), when at least [tau]_1 was loaded
+        if let Some(g1_tau) = kzg.g1.get(1) {
+            let g2_tau = kzg.g2_tau_pow(1)?;
+            if !Self::pairings_verify(
+                *g1_tau,
+                G2Affine::generator(),
+                G1Affine::generator(),
+                g2_tau,
+            ) {
+                return Err(KzgError::SrsTauMismatch);
+            }
+        }
+        Ok(kzg)
+    }
+
+    /// opens a point file and decodes it with the bulk decoder, see `srs::read_points`.
+    fn read_points_from_file<T: PointCodec>(
+        path: &str,
+        points_to_load: Option<usize>,
+        validation: PointValidation,
+    ) -> Result<Vec<T>, KzgError> {
+        let file = File::open(path).map_err(|e| KzgError::from(e).in_file(path))?;
+        srs::read_points(BufReader::new(file), points_to_load, validation, None)
+            .map_err(|e| e.in_file(path))
+    }
+
+    /// An srs of `num_points` powers of a known `tau`, with the lagrange bases of
+    /// every size in `lagrange_sizes` computed from `tau` directly instead of with
+    /// a group IFFT. Anyone knowing `tau` can forge proofs, so this is only for
+    /// benchmarks and tests.
+    #[cfg(feature = "test-utils")]
+    pub fn setup_insecure(
+        tau: Fr,
+        num_points: usize,
+        lagrange_sizes: &[usize],
+    ) -> Result<Self, KzgError> {
+        let mut powers = Vec::with_capacity(num_points);
+        let mut power = Fr::one();
+        for _ in 0..num_points {
+            powers.push(power);
+            power *= tau;
         }
+        let kzg = Self {
+            g1: Self::generator_multiples(&powers),
+            g2: vec![(G2Affine::generator() * tau).into_affine()],
+            params: None,
+            srs_order: num_points as u64,
+            g2_layout: G2Layout::TauOnly,
+            lagrange_cache: LagrangeCache::default(),
+        };
+
+        // L_i(tau) = (tau^n - 1) / n * w^i / (tau - w^i)
+        for &size in lagrange_sizes {
+            if size > num_points {
+                return Err(KzgError::SrsTooSmall {
+                    required: size as u64,
+                    available: num_points as u64,
+                });
+            }
+            let domain = Domain::get(size)?;
+            let mut denominators: Vec<Fr> = domain.roots().iter().map(|w| tau - w).collect();
+            batch_inversion(&mut denominators);
+            let scale = (tau.pow([size as u64]) - Fr::one()) * domain.size_inverse();
+            let scalars: Vec<Fr> = domain
+                .roots()
+                .iter()
+                .zip(&denominators)
+                .map(|(w, inverse)| scale * w * inverse)
+                .collect();
+            kzg.lagrange_cache
+                .insert(Self::generator_multiples(&scalars));
+        }
+        Ok(kzg)
     }
 
-    /// data_setup_custom is a helper function
+    /// `[s]_1` for every `s`, with a fixed-base table over the generator
+    #[cfg(feature = "test-utils")]
+    fn generator_multiples(scalars: &[Fr]) -> Vec<G1Affine> {
+        use ark_ec::scalar_mul::fixed_base::FixedBase;
+
+        let scalar_size = Fr::MODULUS_BIT_SIZE as usize;
+        let window = FixedBase::get_mul_window_size(scalars.len());
+        let table =
+            FixedBase::get_window_table(scalar_size, window, G1Affine::generator().into_group());
+        let points: Vec<G1Projective> = FixedBase::msm(scalar_size, window, &table, scalars);
+        G1Projective::normalize_batch(&points)
+    }
+
+    /// sets up the encoding with `EncodingParams::from_blob_length`
     pub fn data_setup_custom(
         &mut self,
         num_of_nodes: u64,
         padded_input_data_size: u64,
     ) -> Result<(), KzgError> {
-        let floor = u64::try_from(BYTES_PER_FIELD_ELEMENT)
-            .map_err(|e| KzgError::SerializationError(e.to_string()))?;
-        let len_of_data_in_elements = padded_input_data_size.div_ceil(floor);
-        let min_num_chunks = len_of_data_in_elements.div_ceil(num_of_nodes);
-        self.data_setup_mins(min_num_chunks, num_of_nodes)
+        let params = EncodingParams::from_blob_length(padded_input_data_size, num_of_nodes)?;
+        self.set_encoding_params(params)
     }
 
-    ///data_setup_mins sets up the environment per the blob data
+    /// sets up the encoding with `EncodingParams::from_mins`
     pub fn data_setup_mins(
         &mut self,
         min_chunk_length: u64,
         min_num_chunks: u64,
     ) -> Result<(), KzgError> {
-        let mut params = Params {
-            num_chunks: min_num_chunks.next_power_of_two(),
-            chunk_length: min_chunk_length.next_power_of_two(),
-            max_fft_width: 0_u64,
-            completed_setup: false,
-        };
-
-        let number_of_evaluations = params.chunk_length * params.num_chunks;
-        let mut log2_of_evals = number_of_evaluations
-            .to_f64()
-            .unwrap()
-            .log2()
-            .to_u8()
-            .unwrap();
-        params.max_fft_width = 1_u64 << log2_of_evals;
-
-        if params.chunk_length == 1 {
-            log2_of_evals = (2 * params.num_chunks)
-                .to_f64()
-                .unwrap()
-                .log2()
-                .to_u8()
-                .unwrap();
-        }
-
-        if params.chunk_length * params.num_chunks >= self.srs_order {
-            return Err(KzgError::SerializationError(
-                "the supplied encoding parameters are not valid with respect to the SRS."
-                    .to_string(),
-            ));
-        }
-
-        let primitive_roots_of_unity = Self::get_primitive_roots_of_unity();
-        let found_root_of_unity = primitive_roots_of_unity
-            .get(log2_of_evals.to_usize().unwrap())
-            .unwrap();
-        let mut expanded_roots_of_unity = Self::expand_root_of_unity(found_root_of_unity);
-        expanded_roots_of_unity.truncate(expanded_roots_of_unity.len() - 1);
-
-        params.completed_setup = true;
-        self.params = params;
-        self.expanded_roots_of_unity = expanded_roots_of_unity;
+        let params = EncodingParams::from_mins(min_chunk_length, min_num_chunks)?;
+        self.set_encoding_params(params)
+    }
 
+    /// sets the encoding the coset proofs are computed for, once checked against
+    /// the srs
+    pub fn set_encoding_params(&mut self, params: EncodingParams) -> Result<(), KzgError> {
+        params.validate(self.srs_order)?;
+        self.params = Some(params);
         Ok(())
     }
 
-    /// helper function to get the
-    pub fn get_nth_root_of_unity(&self, i: usize) -> Option<&Fr> {
-        self.expanded_roots_of_unity.get(i)
-    }
-
-    ///function to expand the roots based on the configuration
-    fn expand_root_of_unity(root_of_unity: &Fr) -> Vec<Fr> {
-        let mut roots = vec![Fr::one()]; // Initialize with 1
-        roots.push(*root_of_unity); // Add the root of unity
-
-        let mut i = 1;
-        while !roots[i].is_one() {
-            // Continue until the element cycles back to one
-            let this = &roots[i];
-            i += 1;
-            roots.push(this * root_of_unity); // Push the next power of the root of unity
-        }
-        roots
-    }
-
-    /// refer to DA code for more context
-    fn get_primitive_roots_of_unity() -> Vec<Fr> {
-        let data: [&str; 29] = [
-            "1",
-            "21888242871839275222246405745257275088548364400416034343698204186575808495616",
-            "21888242871839275217838484774961031246007050428528088939761107053157389710902",
-            "19540430494807482326159819597004422086093766032135589407132600596362845576832",
-            "14940766826517323942636479241147756311199852622225275649687664389641784935947",
-            "4419234939496763621076330863786513495701855246241724391626358375488475697872",
-            "9088801421649573101014283686030284801466796108869023335878462724291607593530",
-            "10359452186428527605436343203440067497552205259388878191021578220384701716497",
-            "3478517300119284901893091970156912948790432420133812234316178878452092729974",
-            "6837567842312086091520287814181175430087169027974246751610506942214842701774",
-            "3161067157621608152362653341354432744960400845131437947728257924963983317266",
-            "1120550406532664055539694724667294622065367841900378087843176726913374367458",
-            "4158865282786404163413953114870269622875596290766033564087307867933865333818",
-            "197302210312744933010843010704445784068657690384188106020011018676818793232",
-            "20619701001583904760601357484951574588621083236087856586626117568842480512645",
-            "20402931748843538985151001264530049874871572933694634836567070693966133783803",
-            "421743594562400382753388642386256516545992082196004333756405989743524594615",
-            "12650941915662020058015862023665998998969191525479888727406889100124684769509",
-            "11699596668367776675346610687704220591435078791727316319397053191800576917728",
-            "15549849457946371566896172786938980432421851627449396898353380550861104573629",
-            "17220337697351015657950521176323262483320249231368149235373741788599650842711",
-            "13536764371732269273912573961853310557438878140379554347802702086337840854307",
-            "12143866164239048021030917283424216263377309185099704096317235600302831912062",
-            "934650972362265999028062457054462628285482693704334323590406443310927365533",
-            "5709868443893258075976348696661355716898495876243883251619397131511003808859",
-            "19200870435978225707111062059747084165650991997241425080699860725083300967194",
-            "7419588552507395652481651088034484897579724952953562618697845598160172257810",
-            "2082940218526944230311718225077035922214683169814847712455127909555749686340",
-            "19103219067921713944291392827692070036145651957329286315305642004821462161904",
-        ];
-        data.iter()
-            .map(|each| Fr::from_str(each).unwrap())
-            .collect()
+    /// the encoding set up by the data_setup functions or `set_encoding_params`
+    pub fn encoding_params(&self) -> Option<EncodingParams> {
+        self.params
+    }
+
+    /// the root of unity at index `i` of the domain of `domain_size` elements, the
+    /// point `compute_kzg_proof_with_roots_of_unity` opens a polynomial of that
+    /// size at
+    pub fn get_nth_root_of_unity(domain_size: usize, i: usize) -> Result<Fr, KzgError> {
+        let domain = Domain::get(domain_size)?;
+        domain
+            .roots()
+            .get(i)
+            .copied()
+            .ok_or(KzgError::IndexOutOfRange {
+                index: i as u64,
+                len: domain_size as u64,
+            })
     }
 
     /// helper function to get g1 points
@@ -187,43 +347,174 @@ impl Kzg {
         self.g1.to_vec()
     }
 
+    /// [tau^n]_2, or an error when the layout of the g2 points doesn't hold it
+    pub fn g2_tau_pow(&self, n: u64) -> Result<G2Affine, KzgError> {
+        if n == 0 {
+            return Ok(G2Affine::generator());
+        }
+        let index = match self.g2_layout {
+            G2Layout::FullPowers => Some(n),
+            G2Layout::PowersOfTwo => n.is_power_of_two().then(|| n.trailing_zeros().into()),
+            G2Layout::TauOnly => (n == 1).then_some(0),
+        };
+        index
+            .and_then(|index| self.g2.get(index.to_usize()?))
+            .copied()
+            .ok_or(KzgError::G2PowerUnavailable(n))
+    }
+
     /// obtain copy of g2 points
     pub fn get_g2_points(&self) -> Vec<G2Affine> {
         self.g2.to_vec()
     }
 
-    /// commit the actual polynomial with the values setup
+    /// Commits to the polynomial, with the lagrange bases in evaluation form and
+    /// the monomial srs in coefficient form. Both give the same commitment for
+    /// the same polynomial.
     pub fn commit(&self, polynomial: &Polynomial) -> Result<G1Affine, KzgError> {
         if polynomial.len() > self.g1.len() {
-            return Err(KzgError::SerializationError(
-                "polynomial length is not correct".to_string(),
-            ));
+            return Err(KzgError::SrsTooSmall {
+                required: polynomial.len() as u64,
+                available: self.g1.len() as u64,
+            });
         }
 
         // Perform the multi-exponentiation
-        let bases = self.g1_ifft(polynomial.len()).unwrap();
-        match G1Projective::msm(&bases, &polynomial.to_vec()) {
-            Ok(res) => Ok(res.into_affine()),
-            Err(err) => Err(KzgError::CommitError(err.to_string())),
+        match polynomial.form() {
+            PolynomialForm::Eval => self.lagrange_msm(&polynomial.to_vec()),
+            PolynomialForm::Coeff => Ok(msm::<G1Projective>(
+                &self.g1[..polynomial.len()],
+                &polynomial.to_vec(),
+            )?
+            .into_affine()),
         }
     }
 
     /// 4844 compatible helper function
     pub fn blob_to_kzg_commitment(&self, blob: &Blob) -> Result<G1Affine, KzgError> {
-        let polynomial = blob
-            .to_polynomial()
-            .map_err(|err| KzgError::SerializationError(err.to_string()))?;
+        let polynomial = blob.to_polynomial()?;
         let commitment = self.commit(&polynomial)?;
         Ok(commitment)
     }
 
-    /// helper function to work with the library and the env of the kzg instance
+    /// commits to the polynomial in g2, needs the full powers of tau in g2
+    pub fn commit_g2(&self, polynomial: &Polynomial) -> Result<G2Affine, KzgError> {
+        let coeffs = Self::to_coefficients(polynomial)?;
+        let bases = self.g2_powers(0, coeffs.len() as u64)?;
+        Ok(msm::<G2Projective>(&bases, &coeffs)?.into_affine())
+    }
+
+    /// Computes the EigenDA length proof, the g2 commitment to
+    /// `x^(srs_order - n) * p(x)` with `n` the length of the polynomial. Only a
+    /// polynomial of degree below `n` can be shifted that far without running out
+    /// of srs, which is what `verify_length_proof` checks.
+    pub fn compute_length_proof(&self, polynomial: &Polynomial) -> Result<G2Affine, KzgError> {
+        let coeffs = Self::to_coefficients(polynomial)?;
+        let shift =
+            self.srs_order
+                .checked_sub(coeffs.len() as u64)
+                .ok_or(KzgError::SrsTooSmall {
+                    required: coeffs.len() as u64,
+                    available: self.srs_order,
+                })?;
+        let bases = self.g2_powers(shift, coeffs.len() as u64)?;
+        Ok(msm::<G2Projective>(&bases, &coeffs)?.into_affine())
+    }
+
+    /// Checks that the polynomial behind a g2 commitment has fewer than `claimed_len`
+    /// coefficients, the same way the EigenDA contracts do:
+    ///
+    /// e([tau^(srs_order - claimed_len)]_1, length_commitment) == e([1]_1, length_proof)
+    pub fn verify_length_proof(
+        &self,
+        length_commitment: G2Affine,
+        length_proof: G2Affine,
+        claimed_len: u64,
+    ) -> Result<bool, KzgError> {
+        let shift = self
+            .srs_order
+            .checked_sub(claimed_len)
+            .ok_or(KzgError::SrsTooSmall {
+                required: claimed_len,
+                available: self.srs_order,
+            })?;
+        let shifted_g1 = usize::try_from(shift)
+            .ok()
+            .and_then(|shift| self.g1.get(shift))
+            .ok_or(KzgError::SrsTooSmall {
+                required: shift + 1,
+                available: self.g1.len() as u64,
+            })?;
+        Ok(Self::pairings_verify(
+            *shifted_g1,
+            length_commitment,
+            G1Affine::generator(),
+            length_proof,
+        ))
+    }
+
+    /// 4844 compatible helper function, opens the blob at the challenge derived by
+    /// `compute_challenge`
+    pub fn compute_blob_kzg_proof(
+        &self,
+        blob: &Blob,
+        commitment: &G1Affine,
+    ) -> Result<G1Affine, KzgError> {
+        let polynomial = blob.to_polynomial()?;
+        let z_fr = Self::challenge(&polynomial, commitment);
+        let (proof, _) = self.compute_kzg_proof_at(&polynomial, z_fr)?;
+        Ok(proof)
+    }
+
+    /// 4844 compatible helper function, checks a proof made by `compute_blob_kzg_proof`
+    pub fn verify_blob_kzg_proof(
+        &self,
+        blob: &Blob,
+        commitment: &G1Affine,
+        proof: &G1Affine,
+    ) -> Result<bool, KzgError> {
+        let polynomial = blob.to_polynomial()?;
+        let z_fr = Self::challenge(&polynomial, commitment);
+        let domain = Domain::get(polynomial.len())?;
+        let value_fr = Self::evaluate_polynomial_at(&polynomial.to_vec(), domain.roots(), z_fr);
+        Ok(self.verify_kzg_proof(*commitment, *proof, value_fr, z_fr))
+    }
+
+    /// Derives the evaluation point of a blob proof. The transcript hashed with
+    /// sha256 is
+    ///
+    /// `FIAT_SHAMIR_PROTOCOL_DOMAIN || n as u64 big endian || p_0 || ... || p_{n-1} || C`
+    ///
+    /// where `p_i` are the 32 byte big endian field elements of the blob polynomial
+    /// (padded to a power of two) and `C` is the 32 byte gnark compressed commitment.
+    /// The digest is read as a big endian integer and reduced modulo r.
+    pub fn compute_challenge(blob: &Blob, commitment: &G1Affine) -> Result<Fr, KzgError> {
+        Ok(Self::challenge(&blob.to_polynomial()?, commitment))
+    }
+
+    /// `compute_challenge` over the polynomial of the blob
+    fn challenge(polynomial: &Polynomial, commitment: &G1Affine) -> Fr {
+        let mut hasher = Sha256::new();
+        hasher.update(FIAT_SHAMIR_PROTOCOL_DOMAIN);
+        hasher.update((polynomial.len() as u64).to_be_bytes());
+        for element in polynomial.to_vec() {
+            hasher.update(element.into_bigint().to_bytes_be());
+        }
+        hasher.update(helpers::write_g1_point_to_bytes_be(commitment));
+        Fr::from_be_bytes_mod_order(&hasher.finalize())
+    }
+
+    /// Opens the polynomial at the root of unity at `index` of the domain of the
+    /// polynomial's size. Domains are cached per size and shared between threads,
+    /// so polynomials of any size can be opened concurrently without any setup.
     pub fn compute_kzg_proof_with_roots_of_unity(
         &self,
         polynomial: &Polynomial,
         index: u64,
     ) -> Result<G1Affine, KzgError> {
-        self.compute_kzg_proof(polynomial, index, &self.expanded_roots_of_unity)
+        let domain = Domain::get(polynomial.len())?;
+        let usized_index = Self::checked_index(index, domain.size())?;
+        self.compute_domain_proof(polynomial, usized_index, &domain)
     }
 
     /// function to compute the kzg proof given the values.
@@ -231,112 +522,536 @@ impl Kzg {
         &self,
         polynomial: &Polynomial,
         index: u64,
-        root_of_unities: &Vec<Fr>,
+        root_of_unities: &[Fr],
     ) -> Result<G1Affine, KzgError> {
-        if !self.params.completed_setup {
-            return Err(KzgError::GenericError(
-                "setup is not complete, run the data_setup functions".to_string(),
-            ));
+        if polynomial.len() != root_of_unities.len() {
+            return Err(KzgError::LengthMismatch {
+                expected: polynomial.len(),
+                actual: root_of_unities.len(),
+            });
         }
+        let usized_index = Self::checked_index(index, root_of_unities.len())?;
 
-        if polynomial.len() != root_of_unities.len() {
-            return Err(KzgError::GenericError(
-                "inconsistent length between blob and root of unities".to_string(),
-            ));
+        // the cached domain holds the inverses when the roots are the usual ones,
+        // otherwise they're computed with a single batch inversion
+        let domain = Domain::get(root_of_unities.len())?;
+        if domain.roots() == root_of_unities {
+            return self.compute_domain_proof(polynomial, usized_index, &domain);
         }
 
-        let eval_fr = polynomial.to_vec();
-        let mut poly_shift: Vec<Fr> = Vec::with_capacity(eval_fr.len());
-        let usized_index = if let Some(x) = index.to_usize() {
-            x
-        } else {
-            return Err(KzgError::SerializationError(
-                "index couldn't be converted to usize".to_string(),
-            ));
+        let eval_fr = Self::to_evaluations(polynomial)?;
+        let z_fr = root_of_unities[usized_index];
+        let mut inverses: Vec<Fr> = root_of_unities.iter().map(|root| *root - z_fr).collect();
+        batch_inversion(&mut inverses);
+        let z_inverse = z_fr.inverse().ok_or(KzgError::ZeroRootOfUnity)?;
+
+        let quotient_poly = Self::compute_quotient_eval(
+            &eval_fr,
+            eval_fr[usized_index],
+            root_of_unities,
+            &inverses,
+            Some((usized_index, z_inverse)),
+        );
+        self.lagrange_msm(&quotient_poly)
+    }
+
+    fn checked_index(index: u64, len: usize) -> Result<usize, KzgError> {
+        match index.to_usize() {
+            Some(usized_index) if usized_index < len => Ok(usized_index),
+            _ => Err(KzgError::IndexOutOfRange {
+                index,
+                len: len as u64,
+            }),
+        }
+    }
+
+    /// opening at the root at `index` of the domain, which has the polynomial's size
+    fn compute_domain_proof(
+        &self,
+        polynomial: &Polynomial,
+        index: usize,
+        domain: &Domain,
+    ) -> Result<G1Affine, KzgError> {
+        let eval_fr = Self::to_evaluations(polynomial)?;
+        let quotient_poly = Self::compute_quotient_eval(
+            &eval_fr,
+            eval_fr[index],
+            domain.roots(),
+            &domain.inverse_differences(index),
+            Some((index, domain.inverse_root(index))),
+        );
+        self.lagrange_msm(&quotient_poly)
+    }
+
+    /// Quotient `(p(X) - p(z)) / (X - z)` in evaluation form, given
+    /// `inverses[i] = 1 / (w_i - z)`. When `z` is the root at index `m`, the
+    /// quotient there is the closed form `-1/w_m * sum_{i != m} w_i * q(w_i)`,
+    /// which needs `inverses[m]` to be zero and the inverse of `z`.
+    fn compute_quotient_eval(
+        eval_fr: &[Fr],
+        value_fr: Fr,
+        roots_of_unity: &[Fr],
+        inverses: &[Fr],
+        on_domain: Option<(usize, Fr)>,
+    ) -> Vec<Fr> {
+        let mut quotient_poly: Vec<Fr> = eval_fr
+            .iter()
+            .zip(inverses)
+            .map(|(eval, inverse)| (*eval - value_fr) * inverse)
+            .collect();
+
+        if let Some((index, z_inverse)) = on_domain {
+            let weighted_sum: Fr = quotient_poly
+                .iter()
+                .zip(roots_of_unity)
+                .map(|(quotient, root)| *quotient * root)
+                .sum();
+            quotient_poly[index] = -weighted_sum * z_inverse;
+        }
+        quotient_poly
+    }
+
+    /// Opens the polynomial at any point `z`, returning the proof and the value
+    /// `y = p(z)`. Unlike `compute_kzg_proof`, `z` does not have to be a root of
+    /// unity, which is what EIP-4844 style point evaluation needs.
+    pub fn compute_kzg_proof_at(
+        &self,
+        polynomial: &Polynomial,
+        z_fr: Fr,
+    ) -> Result<(G1Affine, Fr), KzgError> {
+        let domain = Domain::get(polynomial.len())?;
+        let eval_fr = Self::to_evaluations(polynomial)?;
+
+        let on_domain = domain.roots().iter().position(|root| *root == z_fr);
+        let (inverses, value_fr) = match on_domain {
+            Some(index) => (domain.inverse_differences(index), eval_fr[index]),
+            None => {
+                let mut inverses: Vec<Fr> =
+                    domain.roots().iter().map(|root| *root - z_fr).collect();
+                batch_inversion(&mut inverses);
+                let value_fr =
+                    Self::evaluate_with_inverses(&eval_fr, domain.roots(), z_fr, &inverses);
+                (inverses, value_fr)
+            }
         };
 
-        let value_fr = eval_fr[usized_index];
-        let z_fr = root_of_unities[usized_index];
+        let quotient_poly = Self::compute_quotient_eval(
+            &eval_fr,
+            value_fr,
+            domain.roots(),
+            &inverses,
+            on_domain.map(|index| (index, domain.inverse_root(index))),
+        );
+        let proof = self.lagrange_msm(&quotient_poly)?;
+        Ok((proof, value_fr))
+    }
+
+    /// the coefficients of the polynomial, whichever its form
+    fn to_coefficients(polynomial: &Polynomial) -> Result<Vec<Fr>, KzgError> {
+        Ok(polynomial.to_coeff_form()?.to_vec())
+    }
+
+    /// the evaluations of the polynomial on the domain of its size, whichever its form
+    fn to_evaluations(polynomial: &Polynomial) -> Result<Vec<Fr>, KzgError> {
+        Ok(polynomial.to_eval_form()?.to_vec())
+    }
+
+    /// evaluates a polynomial given by its evaluations over the roots of unity
+    /// with the barycentric formula
+    fn evaluate_polynomial_at(eval_fr: &[Fr], roots_of_unity: &[Fr], z_fr: Fr) -> Fr {
+        if let Some(index) = roots_of_unity.iter().position(|root| *root == z_fr) {
+            return eval_fr[index];
+        }
+        let mut inverses: Vec<Fr> = roots_of_unity.iter().map(|root| *root - z_fr).collect();
+        batch_inversion(&mut inverses);
+        Self::evaluate_with_inverses(eval_fr, roots_of_unity, z_fr, &inverses)
+    }
+
+    /// p(z) = (z^n - 1) / n * sum(p_i * w_i / (z - w_i)), `inverses` holds 1 / (w_i - z)
+    fn evaluate_with_inverses(
+        eval_fr: &[Fr],
+        roots_of_unity: &[Fr],
+        z_fr: Fr,
+        inverses: &[Fr],
+    ) -> Fr {
+        let n = roots_of_unity.len() as u64;
+        let sum: Fr = eval_fr
+            .iter()
+            .zip(roots_of_unity)
+            .zip(inverses)
+            .map(|((eval, root), inverse)| *eval * root * inverse)
+            .sum();
+        let factor = (Fr::one() - z_fr.pow([n])) / Fr::from(n);
+        sum * factor
+    }
 
-        for i in 0..eval_fr.len() {
-            poly_shift.push(eval_fr[i] - value_fr);
+    /// Computes the proof for one EigenDA chunk. With the encoding params set by the
+    /// data_setup functions, the chunk with index `k` holds the evaluations on the
+    /// coset `h * <w^num_chunks>` with `h = w^k`, where `w` generates the domain of
+    /// `chunk_length * num_chunks` evaluations. The proof commits to the quotient of
+    /// the polynomial by `X^chunk_length - h^chunk_length`. Chunk `i` of
+    /// `encoding::encode_chunks` is the one with index `reverse_bits(i, num_chunks)`.
+    pub fn compute_coset_proof(
+        &self,
+        polynomial: &Polynomial,
+        chunk_index: u64,
+    ) -> Result<G1Affine, KzgError> {
+        let params = self.params.ok_or(KzgError::SetupIncomplete)?;
+        if chunk_index >= params.num_chunks() {
+            return Err(KzgError::IndexOutOfRange {
+                index: chunk_index,
+                len: params.num_chunks(),
+            });
         }
+        let chunk_length = params.chunk_length() as usize;
+        let num_evaluations = params.num_evaluations() as usize;
+        if polynomial.len() > num_evaluations {
+            return Err(KzgError::TooLong {
+                max: num_evaluations,
+                actual: polynomial.len(),
+            });
+        }
+        if polynomial.len() > self.g1.len() {
+            return Err(KzgError::SrsTooSmall {
+                required: polynomial.len() as u64,
+                available: self.g1.len() as u64,
+            });
+        }
+
+        let coset_shift = Domain::get(num_evaluations)?.element(chunk_index as usize);
+        let shift_power = coset_shift.pow([chunk_length as u64]);
 
-        let mut denom_poly = Vec::<Fr>::with_capacity(root_of_unities.len());
-        for i in 0..eval_fr.len() {
-            denom_poly.push(root_of_unities[i] - z_fr);
+        // long division by X^chunk_length - h^chunk_length, the remainder is dropped
+        let mut remainder = Self::to_coefficients(polynomial)?;
+        if remainder.len() <= chunk_length {
+            return Ok(G1Affine::zero());
+        }
+        let mut quotient = vec![Fr::zero(); remainder.len() - chunk_length];
+        for i in (chunk_length..remainder.len()).rev() {
+            quotient[i - chunk_length] = remainder[i];
+            let carry = remainder[i] * shift_power;
+            remainder[i - chunk_length] += carry;
         }
 
-        let mut quotient_poly = Vec::<Fr>::with_capacity(root_of_unities.len());
+        Ok(msm::<G1Projective>(&self.g1[..quotient.len()], &quotient)?.into_affine())
+    }
 
-        for i in 0..root_of_unities.len() {
-            if denom_poly[i].is_zero() {
-                quotient_poly.push(self.compute_quotient_eval_on_domain(
-                    z_fr,
-                    &eval_fr,
-                    value_fr,
-                    &root_of_unities,
-                ));
-            } else {
-                quotient_poly.push(poly_shift[i].div(denom_poly[i]));
+    /// Checks the evaluations of a committed polynomial on the coset
+    /// `coset_shift * <u>`, where `u` is the primitive root of unity of order
+    /// `evaluations.len()`. With `I` the polynomial interpolating the evaluations
+    /// and `l` the number of evaluations, it checks
+    ///
+    /// e(C - [I(tau)], [1]_2) == e(proof, [tau^l - coset_shift^l]_2)
+    pub fn verify_coset_proof(
+        &self,
+        commitment: G1Affine,
+        proof: G1Affine,
+        coset_shift: Fr,
+        evaluations: &[Fr],
+    ) -> Result<bool, KzgError> {
+        let chunk_length = evaluations.len();
+        let domain = Domain::get(chunk_length)?;
+        if chunk_length > self.g1.len() {
+            return Err(KzgError::SrsTooSmall {
+                required: chunk_length as u64,
+                available: self.g1.len() as u64,
+            });
+        }
+
+        let interpolation = domain.coset_ifft(evaluations, coset_shift)?;
+        self.verify_interpolation(commitment, proof, coset_shift, &interpolation)
+    }
+
+    /// Checks a frame of the encoding with these params against the commitment
+    /// the way an EigenDA node does, `VerifyFrame` in the go verifier. Frame `i`
+    /// holds the chunk on the coset `params.chunk_coset_shift(i) * <u>`.
+    pub fn verify_frame(
+        &self,
+        commitment: G1Affine,
+        frame: &Frame,
+        frame_index: u64,
+        params: &EncodingParams,
+    ) -> Result<bool, KzgError> {
+        let chunk_length = params.chunk_length() as usize;
+        if frame.coeffs.len() != chunk_length {
+            return Err(KzgError::LengthMismatch {
+                expected: chunk_length,
+                actual: frame.coeffs.len(),
+            });
+        }
+        if chunk_length > self.g1.len() {
+            return Err(KzgError::SrsTooSmall {
+                required: chunk_length as u64,
+                available: self.g1.len() as u64,
+            });
+        }
+        let coset_shift = params.chunk_coset_shift(frame_index)?;
+        self.verify_interpolation(commitment, frame.proof, coset_shift, &frame.coeffs)
+    }
+
+    /// e(C - [I(tau)], [1]_2) == e(proof, [tau^l - coset_shift^l]_2), given the
+    /// `l` coefficients of `I`
+    fn verify_interpolation(
+        &self,
+        commitment: G1Affine,
+        proof: G1Affine,
+        coset_shift: Fr,
+        interpolation: &[Fr],
+    ) -> Result<bool, KzgError> {
+        let chunk_length = interpolation.len();
+        let interpolation_g1 =
+            msm::<G1Projective>(&self.g1[..chunk_length], interpolation)?.into_affine();
+
+        let shift_power_g2 =
+            (G2Affine::generator() * coset_shift.pow([chunk_length as u64])).into_affine();
+        let vanishing_g2 = (self.g2_tau_pow(chunk_length as u64)? - shift_power_g2).into_affine();
+        let commit_minus_interpolation = (commitment - interpolation_g1).into_affine();
+        Ok(Self::pairings_verify(
+            commit_minus_interpolation,
+            G2Affine::generator(),
+            proof,
+            vanishing_g2,
+        ))
+    }
+
+    /// Encodes the polynomial into the frames of the go encoder, the chunks of
+    /// `encoding::encode_chunks` with their FK20 multiproofs.
+    pub fn compute_frames(
+        &self,
+        polynomial: &Polynomial,
+        params: &EncodingParams,
+    ) -> Result<Vec<Frame>, KzgError> {
+        let chunks = encoding::encode_chunks(polynomial, params)?;
+        let coeffs = Self::to_coefficients(polynomial)?;
+        let proofs = self.fk20_multiproofs(
+            &coeffs,
+            params.chunk_length() as usize,
+            params.num_chunks() as usize,
+        )?;
+        chunks
+            .iter()
+            .zip(0..)
+            .map(|(chunk, i)| {
+                let proof = proofs[encoding::reverse_bits(i, params.num_chunks()) as usize];
+                Frame::from_evaluations(proof, chunk, i, params)
+            })
+            .collect()
+    }
+
+    /// Computes the proofs at every root of unity of the polynomial's domain at once
+    /// with FK20, in O(n log n) group operations instead of O(n^2). `proofs[i]` is
+    /// the one `compute_kzg_proof` returns for index `i`.
+    pub fn compute_all_proofs(&self, polynomial: &Polynomial) -> Result<Vec<G1Affine>, KzgError> {
+        self.compute_multiproofs(polynomial, 1)
+    }
+
+    /// Computes one multi-reveal proof per coset of size `chunk_length` of the
+    /// polynomial's domain with FK20. Proof `k` opens the coset `w^k * <w^num_chunks>`,
+    /// where `w` generates the domain and `num_chunks = n / chunk_length`, which is
+    /// the natural coset order of the EigenDA encoder.
+    pub fn compute_multiproofs(
+        &self,
+        polynomial: &Polynomial,
+        chunk_length: usize,
+    ) -> Result<Vec<G1Affine>, KzgError> {
+        if !chunk_length.is_power_of_two() {
+            return Err(KzgError::InvalidDomainSize(chunk_length));
+        }
+        if chunk_length > polynomial.len() {
+            return Err(KzgError::TooLong {
+                max: polynomial.len(),
+                actual: chunk_length,
+            });
+        }
+        let coeffs = Self::to_coefficients(polynomial)?;
+        self.fk20_multiproofs(&coeffs, chunk_length, polynomial.len() / chunk_length)
+    }
+
+    /// FK20 over the coefficients of a polynomial. The proof for the coset with
+    /// `h^chunk_length = s` is the commitment to the quotient of the division by
+    /// `X^chunk_length - s`, which expands to `sum_e s^e * h_e` with
+    ///
+    /// h_e = sum_t f_{(e + 1) * chunk_length + t} * [tau^t]
+    ///
+    /// Splitting `t` by its residue modulo `chunk_length` turns every `h_e` into a
+    /// sum of `chunk_length` correlations, computed with FFTs of size `2 * num_chunks`.
+    /// The proofs are then the FFT of the `h_e` over the `num_chunks` roots of unity.
+    fn fk20_multiproofs(
+        &self,
+        coeffs: &[Fr],
+        chunk_length: usize,
+        num_chunks: usize,
+    ) -> Result<Vec<G1Affine>, KzgError> {
+        if coeffs.len() > chunk_length * num_chunks {
+            return Err(KzgError::TooLong {
+                max: chunk_length * num_chunks,
+                actual: coeffs.len(),
+            });
+        }
+        if coeffs.len() > self.g1.len() {
+            return Err(KzgError::SrsTooSmall {
+                required: coeffs.len() as u64,
+                available: self.g1.len() as u64,
+            });
+        }
+
+        let toeplitz_domain = Domain::get(2 * num_chunks)?;
+        let proof_domain = Domain::get(num_chunks)?;
+
+        let mut sum = vec![G1Projective::zero(); 2 * num_chunks];
+        for residue in 0..chunk_length {
+            // srs powers with this residue, reversed so the correlation becomes a convolution
+            let mut srs_column = vec![G1Projective::zero(); 2 * num_chunks];
+            let mut coeff_column = vec![Fr::zero(); 2 * num_chunks];
+            for row in 0..num_chunks {
+                let index = row * chunk_length + residue;
+                if index < coeffs.len() {
+                    srs_column[num_chunks - 1 - row] = self.g1[index].into();
+                    coeff_column[row] = coeffs[index];
+                }
+            }
+            let srs_column = toeplitz_domain.fft(&srs_column)?;
+            let coeff_column = toeplitz_domain.fft(&coeff_column)?;
+            for ((acc, point), coeff) in sum.iter_mut().zip(&srs_column).zip(&coeff_column) {
+                *acc += *point * coeff;
             }
         }
 
-        let g1_lagrange = self.g1_ifft(polynomial.len())?;
+        // h_e sits at e + num_chunks of the convolution
+        let convolution = toeplitz_domain.ifft(&sum)?;
+        let proofs = proof_domain.fft(&convolution[num_chunks..])?;
+        Ok(G1Projective::normalize_batch(&proofs))
+    }
 
-        match G1Projective::msm(&g1_lagrange, &quotient_poly) {
-            Ok(res) => Ok(G1Affine::from(res)),
-            Err(err) => Err(KzgError::SerializationError(err.to_string())),
+    /// the lagrange bases of the domain of the given size, derived with `g1_ifft`
+    /// the first time and served from `lagrange_cache` afterwards
+    pub fn g1_lagrange(&self, length: usize) -> Result<Arc<Vec<G1Affine>>, KzgError> {
+        if let Some(bases) = self.lagrange_cache.get(length) {
+            return Ok(bases);
         }
+        let bases = self.g1_ifft(length)?;
+        Ok(self.lagrange_cache.insert(bases))
+    }
+
+    /// the domain sizes whose lagrange bases are cached, smallest first
+    pub fn lagrange_sizes(&self) -> Vec<usize> {
+        self.lagrange_cache.sizes()
+    }
+
+    /// replaces the g1 points, dropping everything derived from the old ones
+    #[cfg(test)]
+    pub(crate) fn set_g1_points(&mut self, g1: Vec<G1Affine>) {
+        self.g1 = g1;
+        self.lagrange_cache.clear();
     }
 
-    /// refer to DA for more context
-    fn compute_quotient_eval_on_domain(
+    /// Builds fixed-base MSM tables over the lagrange bases of every size, each
+    /// within `memory_budget` bytes, buckets of an MSM included. Commitments and
+    /// proofs of those sizes then use the tables instead of a variable-base MSM.
+    pub fn precompute_msm_tables(
         &self,
-        z_fr: Fr,
-        eval_fr: &Vec<Fr>,
-        value_fr: Fr,
-        roots_of_unities: &Vec<Fr>,
-    ) -> Fr {
-        let mut quotient = Fr::zero();
-        let mut fi = Fr::zero();
-        let mut numerator: Fr = Fr::zero();
-        let mut denominator: Fr = Fr::zero();
-        let mut temp: Fr = Fr::zero();
-
-        for i in 0..roots_of_unities.len() {
-            let omega_i = roots_of_unities[i];
-            if omega_i == z_fr {
-                continue;
+        lengths: &[usize],
+        memory_budget: usize,
+    ) -> Result<(), KzgError> {
+        for &length in lengths {
+            let bases = self.g1_lagrange(length)?;
+            let tables = PrecomputedBases::new(&bases, memory_budget)?;
+            self.lagrange_cache.insert_tables(tables);
+        }
+        Ok(())
+    }
+
+    /// `sum scalars[i] * L_i(tau)` over the domain of the size of `scalars`
+    fn lagrange_msm(&self, scalars: &[Fr]) -> Result<G1Affine, KzgError> {
+        if let Some(tables) = self.lagrange_cache.get_tables(scalars.len()) {
+            return Ok(tables.msm(scalars)?.into_affine());
+        }
+        let bases = self.g1_lagrange(scalars.len())?;
+        Ok(msm::<G1Projective>(&bases, scalars)?.into_affine())
+    }
+
+    /// fills the lagrange cache for every size up front
+    pub fn precompute_lagrange_bases(&self, lengths: &[usize]) -> Result<(), KzgError> {
+        for &length in lengths {
+            self.g1_lagrange(length)?;
+        }
+        Ok(())
+    }
+
+    /// writes the lagrange cache to a file, see `LagrangeCache::write_to` for the format
+    pub fn save_lagrange_bases(&self, path: &str) -> Result<(), KzgError> {
+        let file = File::create(path).map_err(|e| KzgError::from(e).in_file(path))?;
+        let mut writer = BufWriter::new(file);
+        self.lagrange_cache
+            .write_to(&mut writer)
+            .map_err(|e| e.in_file(path))?;
+        writer.flush().map_err(|e| KzgError::from(e).in_file(path))
+    }
+
+    /// Loads lagrange bases written by `save_lagrange_bases` into the cache. Every
+    /// size is checked against the srs with a random linear combination, see
+    /// `lagrange_bases_match_srs`, and nothing is cached unless all of them match.
+    pub fn load_lagrange_bases(
+        &self,
+        path: &str,
+        validation: PointValidation,
+    ) -> Result<(), KzgError> {
+        let file = File::open(path).map_err(|e| KzgError::from(e).in_file(path))?;
+        let all_bases = LagrangeCache::read_from(BufReader::new(file), validation)
+            .map_err(|e| e.in_file(path))?;
+
+        for bases in &all_bases {
+            if !self.lagrange_bases_match_srs(bases) {
+                return Err(KzgError::LagrangeBasesMismatch(bases.len()));
             }
-            fi = eval_fr[i] - value_fr;
-            numerator = fi.mul(omega_i);
-            denominator = z_fr - omega_i;
-            denominator = denominator * z_fr;
-            temp = numerator.div(denominator);
-            quotient = quotient + temp;
         }
-        quotient
+        for bases in all_bases {
+            self.lagrange_cache.insert(bases);
+        }
+        Ok(())
+    }
+
+    /// Checks `sum r_i * L_i(tau) = sum IFFT(r)_j * tau^j` for a fresh random `r`.
+    /// The bases are the IFFT of the srs iff this holds for every `r`, so bases
+    /// that aren't only pass with negligible probability.
+    fn lagrange_bases_match_srs(&self, bases: &[G1Affine]) -> bool {
+        let Ok(domain) = Domain::get(bases.len()) else {
+            return false;
+        };
+        if bases.len() > self.g1.len() {
+            return false;
+        }
+        let rng = &mut rand::thread_rng();
+        let scalars: Vec<Fr> = (0..bases.len()).map(|_| Fr::rand(rng)).collect();
+        let Ok(monomial_scalars) = domain.ifft(&scalars) else {
+            return false;
+        };
+        match (
+            msm::<G1Projective>(bases, &scalars),
+            msm::<G1Projective>(&self.g1[..bases.len()], &monomial_scalars),
+        ) {
+            (Ok(lagrange), Ok(monomial)) => lagrange == monomial,
+            _ => false,
+        }
     }
 
     /// function to compute the inverse FFT
     pub fn g1_ifft(&self, length: usize) -> Result<Vec<G1Affine>, KzgError> {
-        // is not power of 2
-        if !length.is_power_of_two() {
-            return Err(KzgError::FftError(
-                "length provided is not a power of 2".to_string(),
-            ));
+        let domain = Domain::get(length)?;
+        if length > self.g1.len() {
+            return Err(KzgError::SrsTooSmall {
+                required: length as u64,
+                available: self.g1.len() as u64,
+            });
         }
 
-        let domain = GeneralEvaluationDomain::<Fr>::new(length)
-            .expect("Failed to construct domain for IFFT");
         let points_projective: Vec<G1Projective> = self.g1[..length]
             .iter()
             .map(|&p| G1Projective::from(p))
             .collect();
 
         // Perform the IFFT
-        let ifft_result = domain.ifft(&points_projective);
+        let ifft_result = domain.ifft(&points_projective)?;
         let ifft_result_affine: Vec<_> = ifft_result.iter().map(|p| p.into_affine()).collect();
         Ok(ifft_result_affine)
     }
@@ -348,10 +1063,8 @@ impl Kzg {
         value_fr: Fr,
         z_fr: Fr,
     ) -> bool {
-        let g2_tau = if self.g2.len() > 28 {
-            self.g2.get(1).unwrap().clone()
-        } else {
-            self.g2.get(0).unwrap().clone()
+        let Ok(g2_tau) = self.g2_tau_pow(1) else {
+            return false;
         };
         let value_g1 = (G1Affine::generator() * value_fr).into_affine();
         let commit_minus_value = (commitment - value_g1).into_affine();
@@ -360,6 +1073,154 @@ impl Kzg {
         Self::pairings_verify(commit_minus_value, G2Affine::generator(), proof, x_minus_z)
     }
 
+    /// Verifies many openings at once. Every opening is weighted by a power of a
+    /// Fiat-Shamir challenge derived from all the inputs, and the weighted sum is
+    /// checked with a single two-pairing equation:
+    ///
+    /// e(sum r^i (C_i - [y_i] + z_i * pi_i), [1]_2) == e(sum r^i pi_i, [tau]_2)
+    ///
+    /// The arguments line up with `verify_kzg_proof`: `value_frs[i]` is the claimed
+    /// evaluation of `commitments[i]` at `z_frs[i]`.
+    pub fn verify_kzg_proof_batch(
+        &self,
+        commitments: &[G1Affine],
+        proofs: &[G1Affine],
+        value_frs: &[Fr],
+        z_frs: &[Fr],
+    ) -> Result<bool, KzgError> {
+        let n = commitments.len();
+        let lengths = [proofs.len(), value_frs.len(), z_frs.len()];
+        if let Some(&actual) = lengths.iter().find(|&&len| len != n) {
+            return Err(KzgError::LengthMismatch {
+                expected: n,
+                actual,
+            });
+        }
+        if n == 0 {
+            return Ok(true);
+        }
+
+        let weights = Self::compute_batch_weights(commitments, proofs, value_frs, z_frs);
+        let weighted_z: Vec<Fr> = weights.iter().zip(z_frs).map(|(r, z)| *r * z).collect();
+        let weighted_value: Fr = weights.iter().zip(value_frs).map(|(r, y)| *r * y).sum();
+
+        let commitments_sum = msm::<G1Projective>(commitments, &weights)?;
+        let proofs_z_sum = msm::<G1Projective>(proofs, &weighted_z)?;
+        let proofs_sum = msm::<G1Projective>(proofs, &weights)?;
+
+        let lhs = commitments_sum - G1Affine::generator() * weighted_value + proofs_z_sum;
+        Ok(Self::pairings_verify(
+            lhs.into_affine(),
+            G2Affine::generator(),
+            proofs_sum.into_affine(),
+            self.g2_tau_pow(1)?,
+        ))
+    }
+
+    /// Same as `verify_kzg_proof_batch`, but when the batch check fails every
+    /// opening is verified on its own to find out which ones are invalid.
+    pub fn verify_kzg_proof_batch_with_fallback(
+        &self,
+        commitments: &[G1Affine],
+        proofs: &[G1Affine],
+        value_frs: &[Fr],
+        z_frs: &[Fr],
+    ) -> Result<BatchVerification, KzgError> {
+        if self.verify_kzg_proof_batch(commitments, proofs, value_frs, z_frs)? {
+            return Ok(BatchVerification::Valid);
+        }
+
+        let failing = (0..commitments.len())
+            .filter(|&i| !self.verify_kzg_proof(commitments[i], proofs[i], value_frs[i], z_frs[i]))
+            .collect();
+        Ok(BatchVerification::Invalid(failing))
+    }
+
+    /// Checks that a g1 and a g2 commitment bind the same polynomial,
+    /// e(g1_commitment, [1]_2) == e([1]_1, g2_commitment).
+    pub fn verify_commitment_equivalence(
+        &self,
+        g1_commitment: G1Affine,
+        g2_commitment: G2Affine,
+    ) -> bool {
+        Self::pairings_verify(
+            g1_commitment,
+            G2Affine::generator(),
+            G1Affine::generator(),
+            g2_commitment,
+        )
+    }
+
+    /// Checks many commitment pairs at once. The pairs are folded with powers of a
+    /// Fiat-Shamir challenge derived from all the commitments into a single check
+    /// e(sum r^i C1_i, [1]_2) == e([1]_1, sum r^i C2_i).
+    pub fn verify_commitment_equivalence_batch(
+        &self,
+        g1_commitments: &[G1Affine],
+        g2_commitments: &[G2Affine],
+    ) -> Result<bool, KzgError> {
+        if g1_commitments.len() != g2_commitments.len() {
+            return Err(KzgError::LengthMismatch {
+                expected: g1_commitments.len(),
+                actual: g2_commitments.len(),
+            });
+        }
+        if g1_commitments.is_empty() {
+            return Ok(true);
+        }
+
+        let mut hasher = Sha256::new();
+        hasher.update(COMMITMENT_EQUIVALENCE_DOMAIN);
+        hasher.update((g1_commitments.len() as u64).to_be_bytes());
+        for (g1_commitment, g2_commitment) in g1_commitments.iter().zip(g2_commitments) {
+            hasher.update(helpers::write_g1_point_to_bytes_be(g1_commitment));
+            hasher.update(helpers::write_g2_point_to_bytes_be(g2_commitment));
+        }
+        let weights = Self::challenge_powers(hasher, g1_commitments.len());
+
+        let g1_sum = msm::<G1Projective>(g1_commitments, &weights)?;
+        let g2_sum = msm::<G2Projective>(g2_commitments, &weights)?;
+        Ok(self.verify_commitment_equivalence(g1_sum.into_affine(), g2_sum.into_affine()))
+    }
+
+    /// derives the weights of a batch as powers of a challenge hashed from every input,
+    /// so a prover can't pick proofs that cancel out in the weighted sum.
+    fn compute_batch_weights(
+        commitments: &[G1Affine],
+        proofs: &[G1Affine],
+        value_frs: &[Fr],
+        z_frs: &[Fr],
+    ) -> Vec<Fr> {
+        let mut hasher = Sha256::new();
+        hasher.update(KZG_BATCH_DOMAIN);
+        hasher.update((commitments.len() as u64).to_be_bytes());
+        for i in 0..commitments.len() {
+            hasher.update(helpers::write_g1_point_to_bytes_be(&commitments[i]));
+            hasher.update(helpers::write_g1_point_to_bytes_be(&proofs[i]));
+            hasher.update(value_frs[i].into_bigint().to_bytes_be());
+            hasher.update(z_frs[i].into_bigint().to_bytes_be());
+        }
+        Self::challenge_powers(hasher, commitments.len())
+    }
+
+    /// reads the challenge out of the hashed transcript and returns its first `count` powers
+    fn challenge_powers(transcript: Sha256, count: usize) -> Vec<Fr> {
+        let challenge = Fr::from_be_bytes_mod_order(&transcript.finalize());
+
+        let mut weights = Vec::with_capacity(count);
+        let mut weight = Fr::one();
+        for _ in 0..count {
+            weights.push(weight);
+            weight *= challenge;
+        }
+        weights
+    }
+
+    /// `len` consecutive powers of tau in g2 starting at `start`
+    fn g2_powers(&self, start: u64, len: u64) -> Result<Vec<G2Affine>, KzgError> {
+        (start..start + len).map(|n| self.g2_tau_pow(n)).collect()
+    }
+
     fn pairings_verify(a1: G1Affine, a2: G2Affine, b1: G1Affine, b2: G2Affine) -> bool {
         let neg_b1 = -b1;
         let p = [a1, neg_b1];
@@ -369,16 +1230,38 @@ impl Kzg {
     }
 }
 
+/// `VariableBaseMSM::msm`, which only fails when the lengths differ
+fn msm<G: VariableBaseMSM>(
+    bases: &[G::MulBase],
+    scalars: &[G::ScalarField],
+) -> Result<G, KzgError> {
+    G::msm(bases, scalars).map_err(|_| KzgError::LengthMismatch {
+        expected: bases.len(),
+        actual: scalars.len(),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::consts::BYTES_PER_FIELD_ELEMENT;
+    use crate::polynomial::evaluate_coeffs;
+    use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+    use ark_std::str::FromStr;
     use lazy_static::lazy_static;
     use std::env;
 
     // Function to determine the setup based on an environment variable
     fn determine_setup() -> Kzg {
         match env::var("KZG_ENV") {
-            Ok(val) if val == "mainnet-data" => Kzg::setup(false).unwrap(),
+            Ok(val) if val == "mainnet-data" => Kzg::setup_from_files(
+                "src/test-files/mainnet-data/g1.point",
+                "",
+                "src/test-files/mainnet-data/g2.point.powerOf2",
+                268435456,
+                131072,
+            )
+            .unwrap(),
             _ => Kzg::setup(true).unwrap(),
         }
     }
@@ -390,9 +1273,206 @@ mod tests {
     }
 
     #[test]
-    fn test_commit_errors() {
-        let mut poly = vec![];
-        for _ in 0..4000 {
+    fn test_setup_errors() {
+        let result = Kzg::setup_from_files(
+            "src/test-files/g1.point",
+            "src/test-files/g2.point",
+            "",
+            3000,
+            3001,
+        );
+        assert_eq!(
+            result,
+            Err(KzgError::SrsTooSmall {
+                required: 3001,
+                available: 3000
+            })
+        );
+
+        let result = Kzg::setup_from_files("src/test-files/g1.point", "", "", 3000, 3000);
+        assert_eq!(result, Err(KzgError::MissingG2Points));
+
+        let result = Kzg::setup_from_files(
+            "src/test-files/g1.point",
+            "src/test-files/g2.point",
+            "",
+            4000,
+            3500,
+        );
+        assert_eq!(
+            result,
+            Err(KzgError::File {
+                path: "src/test-files/g1.point".to_string(),
+                source: Box::new(KzgError::NotEnoughPoints {
+                    expected: 3500,
+                    found: 3000
+                })
+            })
+        );
+
+        let error = Kzg::setup_from_files("does/not/exist", "", "", 3000, 3000).unwrap_err();
+        match error {
+            KzgError::File { path, source } => {
+                assert_eq!(path, "does/not/exist");
+                assert!(matches!(
+                    *source,
+                    KzgError::Io {
+                        kind: std::io::ErrorKind::NotFound,
+                        ..
+                    }
+                ));
+            }
+            other => panic!("unexpected error {:?}", other),
+        }
+        assert_eq!(Kzg::setup(false), Err(KzgError::MainnetSrsNotBundled));
+    }
+
+    #[test]
+    fn test_data_setup_errors() {
+        let mut kzg = KZG_3000.clone();
+        assert_eq!(
+            kzg.data_setup_custom(0, 1024),
+            Err(KzgError::InvalidEncodingParams {
+                chunk_length: 0,
+                num_chunks: 0
+            })
+        );
+        assert_eq!(
+            kzg.data_setup_mins(u64::MAX, 2),
+            Err(KzgError::InvalidEncodingParams {
+                chunk_length: u64::MAX,
+                num_chunks: 2
+            })
+        );
+        assert_eq!(
+            kzg.data_setup_mins(1 << 40, 1 << 40),
+            Err(KzgError::InvalidEncodingParams {
+                chunk_length: 1 << 40,
+                num_chunks: 1 << 40
+            })
+        );
+        assert_eq!(
+            kzg.data_setup_mins(64, 64),
+            Err(KzgError::SrsTooSmall {
+                required: 4096,
+                available: 3000
+            })
+        );
+        assert_eq!(kzg.encoding_params(), None);
+
+        // 1000 bytes over 3 nodes round up to 4 chunks of 16
+        kzg.data_setup_custom(3, 1000).unwrap();
+        let params = kzg.encoding_params().unwrap();
+        assert_eq!((params.chunk_length(), params.num_chunks()), (16, 4));
+    }
+
+    #[test]
+    fn test_get_nth_root_of_unity() {
+        assert_eq!(
+            Kzg::get_nth_root_of_unity(64, 1),
+            Ok(Domain::get(64).unwrap().generator())
+        );
+        assert_eq!(Kzg::get_nth_root_of_unity(64, 0), Ok(Fr::one()));
+        assert_eq!(
+            Kzg::get_nth_root_of_unity(64, 64),
+            Err(KzgError::IndexOutOfRange { index: 64, len: 64 })
+        );
+        assert_eq!(
+            Kzg::get_nth_root_of_unity(48, 0),
+            Err(KzgError::InvalidDomainSize(48))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "test-utils")]
+    fn test_setup_insecure() {
+        let tau = Fr::from(12345u64);
+        let kzg = Kzg::setup_insecure(tau, 64, &[16, 64]).unwrap();
+        assert_eq!(kzg.lagrange_sizes(), vec![16, 64]);
+        assert_eq!(*kzg.g1_lagrange(16).unwrap(), kzg.g1_ifft(16).unwrap());
+        assert_eq!(*kzg.g1_lagrange(64).unwrap(), kzg.g1_ifft(64).unwrap());
+
+        let elements: Vec<Fr> = (0..16u64).map(Fr::from).collect();
+        let polynomial = Polynomial::new(&elements, 16 * BYTES_PER_FIELD_ELEMENT).unwrap();
+        let commitment = kzg.commit(&polynomial).unwrap();
+        let proof = kzg
+            .compute_kzg_proof_with_roots_of_unity(&polynomial, 5)
+            .unwrap();
+        let z_fr = Domain::get(16).unwrap().element(5);
+        assert!(kzg.verify_kzg_proof(commitment, proof, elements[5], z_fr));
+
+        assert_eq!(
+            Kzg::setup_insecure(tau, 8, &[16]),
+            Err(KzgError::SrsTooSmall {
+                required: 16,
+                available: 8
+            })
+        );
+    }
+
+    #[test]
+    fn test_setup_from_files_matches_bundled_srs() {
+        let kzg = Kzg::setup_from_files(
+            "src/test-files/g1.point",
+            "src/test-files/g2.point",
+            "",
+            3000,
+            3000,
+        )
+        .unwrap();
+        assert_eq!(kzg.g1, KZG_3000.g1);
+        assert_eq!(kzg.g2, KZG_3000.g2);
+        assert_eq!(kzg.srs_order, KZG_3000.srs_order);
+
+        let kzg_partial = Kzg::setup_from_files(
+            "src/test-files/g1.point",
+            "",
+            "src/test-files/g2.point.powerOf2.3000",
+            3000,
+            1000,
+        )
+        .unwrap();
+        assert_eq!(kzg_partial.g1, KZG_3000.g1[..1000].to_vec());
+        assert_eq!(kzg_partial.g2.len(), 12);
+        assert_eq!(kzg_partial.g2_layout, G2Layout::PowersOfTwo);
+        assert_eq!(kzg.g2_layout, G2Layout::FullPowers);
+        for k in 0..12 {
+            assert_eq!(
+                kzg_partial.g2_tau_pow(1 << k),
+                kzg.g2_tau_pow(1 << k),
+                "tau^(2^{})",
+                k
+            );
+        }
+
+        // the powers of two of the mainnet srs come from another tau
+        assert_eq!(
+            Kzg::setup_from_files(
+                "src/test-files/g1.point",
+                "",
+                "src/test-files/mainnet-data/g2.point.powerOf2",
+                3000,
+                1000,
+            ),
+            Err(KzgError::SrsTauMismatch)
+        );
+
+        let kzg_trusted = Kzg::setup_from_files_with_validation(
+            "src/test-files/g1.point",
+            "src/test-files/g2.point",
+            "",
+            3000,
+            3000,
+            PointValidation::Trusted,
+        )
+        .unwrap();
+        assert_eq!(kzg_trusted, kzg);
+    }
+
+    #[test]
+    fn test_commit_errors() {
+        let mut poly = vec![];
+        for _ in 0..4000 {
             poly.push(Fr::one());
         }
 
@@ -400,9 +1480,10 @@ mod tests {
         let result = KZG_3000.commit(&polynomial);
         assert_eq!(
             result,
-            Err(KzgError::SerializationError(
-                "polynomial length is not correct".to_string()
-            ))
+            Err(KzgError::SrsTooSmall {
+                required: 4096,
+                available: 3000
+            })
         );
     }
 
@@ -426,24 +1507,57 @@ mod tests {
         assert_eq!(commitment_from_da, fn_output);
     }
 
+    #[test]
+    fn test_polynomial_forms() {
+        use crate::consts::GETTYSBURG_ADDRESS_BYTES;
+
+        let kzg = KZG_3000.clone();
+        let blob = Blob::from_bytes_and_pad(GETTYSBURG_ADDRESS_BYTES);
+        let evals = blob.to_polynomial().unwrap();
+        let coeffs = evals.to_coeff_form().unwrap();
+        let commitment = kzg.commit(&evals).unwrap();
+        assert_eq!(kzg.commit(&coeffs), Ok(commitment));
+        assert_eq!(kzg.commit_g2(&coeffs), kzg.commit_g2(&evals));
+
+        // proofs don't depend on the form either
+        for index in [0, 5, 31] {
+            assert_eq!(
+                kzg.compute_kzg_proof_with_roots_of_unity(&coeffs, index),
+                kzg.compute_kzg_proof_with_roots_of_unity(&evals, index)
+            );
+        }
+        let z_fr = Fr::from(1234u64);
+        let (proof, value) = kzg.compute_kzg_proof_at(&coeffs, z_fr).unwrap();
+        assert_eq!(kzg.compute_kzg_proof_at(&evals, z_fr), Ok((proof, value)));
+        assert!(kzg.verify_kzg_proof(commitment, proof, value, z_fr));
+
+        // the same blob read as coefficients is another polynomial
+        let blob_coeffs = blob.to_polynomial_with_form(PolynomialForm::Coeff).unwrap();
+        assert_eq!(blob_coeffs.to_vec(), evals.to_vec());
+        let coeff_commitment = kzg.commit(&blob_coeffs).unwrap();
+        assert_ne!(coeff_commitment, commitment);
+        assert_eq!(
+            kzg.commit(&blob_coeffs.to_eval_form().unwrap()),
+            Ok(coeff_commitment)
+        );
+    }
+
     #[test]
     fn test_compute_kzg_proof() {
         use crate::consts::GETTYSBURG_ADDRESS_BYTES;
         use rand::Rng;
 
-        let mut kzg = KZG_INSTANCE.clone();
+        let kzg = KZG_INSTANCE.clone();
 
         let input = Blob::from_bytes_and_pad(GETTYSBURG_ADDRESS_BYTES);
         let input_poly = input.to_polynomial().unwrap();
 
         for index in 0..input_poly.len() - 1 {
             // let index = rand::thread_rng().gen_range(0..input_poly.len());
-            kzg.data_setup_custom(4, input.len().try_into().unwrap())
-                .unwrap();
-            let mut rand_index = rand::thread_rng().gen_range(0..kzg.expanded_roots_of_unity.len());
+            let mut rand_index = rand::thread_rng().gen_range(0..input_poly.len());
             loop {
                 if index == rand_index {
-                    rand_index = rand::thread_rng().gen_range(0..kzg.expanded_roots_of_unity.len());
+                    rand_index = rand::thread_rng().gen_range(0..input_poly.len());
                 } else {
                     break;
                 }
@@ -453,19 +1567,603 @@ mod tests {
                 .compute_kzg_proof_with_roots_of_unity(&input_poly, index.try_into().unwrap())
                 .unwrap();
             let value_fr = input_poly.get_at_index(index).unwrap();
-            let z_fr = kzg.get_nth_root_of_unity(index).unwrap();
-            let pairing_result =
-                kzg.verify_kzg_proof(commitment, proof, value_fr.clone(), z_fr.clone());
+            let z_fr = Kzg::get_nth_root_of_unity(input_poly.len(), index).unwrap();
+            let pairing_result = kzg.verify_kzg_proof(commitment, proof, *value_fr, z_fr);
             assert_eq!(pairing_result, true);
             assert_eq!(
                 kzg.verify_kzg_proof(
                     commitment,
                     proof,
-                    value_fr.clone(),
-                    kzg.get_nth_root_of_unity(rand_index).unwrap().clone()
+                    *value_fr,
+                    Kzg::get_nth_root_of_unity(input_poly.len(), rand_index).unwrap()
                 ),
                 false
             )
         }
     }
+
+    #[test]
+    fn test_compute_kzg_proof_without_setup() {
+        use ark_std::UniformRand;
+
+        // one shared instance, no data_setup, a different polynomial size per thread
+        let kzg = &*KZG_INSTANCE;
+        std::thread::scope(|scope| {
+            for size in [8, 16, 32, 64] {
+                scope.spawn(move || {
+                    let rng = &mut rand::thread_rng();
+                    let evals: Vec<Fr> = (0..size).map(|_| Fr::rand(rng)).collect();
+                    let poly = Polynomial::new(&evals, size * BYTES_PER_FIELD_ELEMENT).unwrap();
+                    let commitment = kzg.commit(&poly).unwrap();
+                    let roots = Domain::get(size).unwrap().roots().to_vec();
+
+                    for index in [0, 1, size - 1] {
+                        let proof = kzg
+                            .compute_kzg_proof_with_roots_of_unity(&poly, index as u64)
+                            .unwrap();
+                        assert!(kzg.verify_kzg_proof(
+                            commitment,
+                            proof,
+                            evals[index],
+                            roots[index]
+                        ));
+                        assert_eq!(
+                            kzg.compute_kzg_proof(&poly, index as u64, &roots),
+                            Ok(proof)
+                        );
+                    }
+                    assert_eq!(
+                        kzg.compute_kzg_proof_with_roots_of_unity(&poly, size as u64),
+                        Err(KzgError::IndexOutOfRange {
+                            index: size as u64,
+                            len: size as u64
+                        })
+                    );
+                });
+            }
+        });
+    }
+
+    #[test]
+    fn test_verify_kzg_proof_batch() {
+        use crate::consts::GETTYSBURG_ADDRESS_BYTES;
+
+        let kzg = KZG_INSTANCE.clone();
+        let input = Blob::from_bytes_and_pad(GETTYSBURG_ADDRESS_BYTES);
+        let input_poly = input.to_polynomial().unwrap();
+        let commitment = kzg.commit(&input_poly).unwrap();
+
+        let indices = [0, 3, 17, 42, 63];
+        let commitments = vec![commitment; indices.len()];
+        let mut proofs = vec![];
+        let mut values = vec![];
+        let mut points = vec![];
+        for index in indices {
+            proofs.push(
+                kzg.compute_kzg_proof_with_roots_of_unity(&input_poly, index as u64)
+                    .unwrap(),
+            );
+            values.push(*input_poly.get_at_index(index).unwrap());
+            points.push(Kzg::get_nth_root_of_unity(input_poly.len(), index).unwrap());
+        }
+
+        assert_eq!(
+            kzg.verify_kzg_proof_batch(&commitments, &proofs, &values, &points),
+            Ok(true)
+        );
+        assert_eq!(
+            kzg.verify_kzg_proof_batch_with_fallback(&commitments, &proofs, &values, &points),
+            Ok(BatchVerification::Valid)
+        );
+        assert_eq!(kzg.verify_kzg_proof_batch(&[], &[], &[], &[]), Ok(true));
+
+        let mut bad_values = values.clone();
+        bad_values[3] += Fr::one();
+        assert_eq!(
+            kzg.verify_kzg_proof_batch(&commitments, &proofs, &bad_values, &points),
+            Ok(false)
+        );
+        assert_eq!(
+            kzg.verify_kzg_proof_batch_with_fallback(&commitments, &proofs, &bad_values, &points),
+            Ok(BatchVerification::Invalid(vec![3]))
+        );
+
+        // swapping two proofs must not go unnoticed even though the sums are unchanged
+        let mut swapped = proofs.clone();
+        swapped.swap(0, 1);
+        assert_eq!(
+            kzg.verify_kzg_proof_batch_with_fallback(&commitments, &swapped, &values, &points),
+            Ok(BatchVerification::Invalid(vec![0, 1]))
+        );
+
+        assert_eq!(
+            kzg.verify_kzg_proof_batch(&commitments, &proofs[1..], &values, &points),
+            Err(KzgError::LengthMismatch {
+                expected: commitments.len(),
+                actual: commitments.len() - 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_compute_kzg_proof_at() {
+        use crate::consts::GETTYSBURG_ADDRESS_BYTES;
+        use ark_std::UniformRand;
+
+        let kzg = KZG_INSTANCE.clone();
+        let input = Blob::from_bytes_and_pad(GETTYSBURG_ADDRESS_BYTES);
+        let input_poly = input.to_polynomial().unwrap();
+        let commitment = kzg.commit(&input_poly).unwrap();
+
+        let rng = &mut rand::thread_rng();
+        for _ in 0..5 {
+            let z_fr = Fr::rand(rng);
+            let (proof, value_fr) = kzg.compute_kzg_proof_at(&input_poly, z_fr).unwrap();
+            assert!(kzg.verify_kzg_proof(commitment, proof, value_fr, z_fr));
+            assert!(!kzg.verify_kzg_proof(commitment, proof, value_fr + Fr::one(), z_fr));
+        }
+
+        // a point on the domain gives the same opening as compute_kzg_proof
+        for index in [0, 1, 31, 63] {
+            let z_fr = Kzg::get_nth_root_of_unity(input_poly.len(), index).unwrap();
+            let (proof, value_fr) = kzg.compute_kzg_proof_at(&input_poly, z_fr).unwrap();
+            assert_eq!(value_fr, *input_poly.get_at_index(index).unwrap());
+            assert_eq!(
+                proof,
+                kzg.compute_kzg_proof_with_roots_of_unity(&input_poly, index as u64)
+                    .unwrap()
+            );
+        }
+
+        // the barycentric evaluation agrees with the coefficient form
+        let domain = GeneralEvaluationDomain::<Fr>::new(input_poly.len()).unwrap();
+        let coeffs = domain.ifft(&input_poly.to_vec());
+        let z_fr = Fr::rand(rng);
+        let expected = evaluate_coeffs(&coeffs, z_fr);
+        let (_, value_fr) = kzg.compute_kzg_proof_at(&input_poly, z_fr).unwrap();
+        assert_eq!(value_fr, expected);
+    }
+
+    #[test]
+    fn test_blob_kzg_proof() {
+        use crate::consts::GETTYSBURG_ADDRESS_BYTES;
+
+        let kzg = KZG_INSTANCE.clone();
+        let blob = Blob::from_bytes_and_pad(GETTYSBURG_ADDRESS_BYTES);
+        let commitment = kzg.blob_to_kzg_commitment(&blob).unwrap();
+        let proof = kzg.compute_blob_kzg_proof(&blob, &commitment).unwrap();
+        assert_eq!(
+            kzg.verify_blob_kzg_proof(&blob, &commitment, &proof),
+            Ok(true)
+        );
+
+        let other_blob = Blob::from_bytes_and_pad(&GETTYSBURG_ADDRESS_BYTES[1..]);
+        let other_commitment = kzg.blob_to_kzg_commitment(&other_blob).unwrap();
+        assert_eq!(
+            kzg.verify_blob_kzg_proof(&other_blob, &commitment, &proof),
+            Ok(false)
+        );
+        assert_eq!(
+            kzg.verify_blob_kzg_proof(&blob, &other_commitment, &proof),
+            Ok(false)
+        );
+        assert_eq!(
+            kzg.verify_blob_kzg_proof(&blob, &commitment, &commitment),
+            Ok(false)
+        );
+
+        // the challenge follows the documented transcript
+        let polynomial = blob.to_polynomial().unwrap();
+        let mut transcript = b"EIGENDA_FSBLOBVERIFY_V1_".to_vec();
+        transcript.extend((polynomial.len() as u64).to_be_bytes());
+        for element in polynomial.to_vec() {
+            transcript.extend(element.into_bigint().to_bytes_be());
+        }
+        transcript.extend(helpers::write_g1_point_to_bytes_be(&commitment));
+        let expected = Fr::from_be_bytes_mod_order(&Sha256::digest(&transcript));
+        assert_eq!(Kzg::compute_challenge(&blob, &commitment), Ok(expected));
+    }
+
+    #[test]
+    fn test_compute_all_proofs() {
+        use crate::consts::GETTYSBURG_ADDRESS_BYTES;
+
+        let kzg = KZG_INSTANCE.clone();
+        let input = Blob::from_bytes_and_pad(GETTYSBURG_ADDRESS_BYTES);
+        let input_poly = input.to_polynomial().unwrap();
+
+        let proofs = kzg.compute_all_proofs(&input_poly).unwrap();
+        assert_eq!(proofs.len(), input_poly.len());
+        for (index, proof) in proofs.iter().enumerate() {
+            let expected = kzg
+                .compute_kzg_proof_with_roots_of_unity(&input_poly, index as u64)
+                .unwrap();
+            assert_eq!(*proof, expected, "proof {}", index);
+        }
+    }
+
+    #[test]
+    fn test_compute_multiproofs() {
+        use crate::consts::GETTYSBURG_ADDRESS_BYTES;
+
+        let kzg = KZG_INSTANCE.clone();
+        let input = Blob::from_bytes_and_pad(GETTYSBURG_ADDRESS_BYTES);
+        let input_poly = input.to_polynomial().unwrap();
+        let n = input_poly.len();
+        let domain = GeneralEvaluationDomain::<Fr>::new(n).unwrap();
+        let coeffs = domain.ifft(&input_poly.to_vec());
+
+        for chunk_length in [1, 2, 8, 64] {
+            let num_chunks = n / chunk_length;
+            let proofs = kzg.compute_multiproofs(&input_poly, chunk_length).unwrap();
+            assert_eq!(proofs.len(), num_chunks);
+
+            for (k, proof) in proofs.iter().enumerate() {
+                // quotient of the long division by X^chunk_length - s, where s = (w^k)^chunk_length
+                let s = domain.element(k).pow([chunk_length as u64]);
+                let mut remainder = coeffs.clone();
+                let mut quotient = vec![Fr::zero(); n - chunk_length];
+                for i in (chunk_length..n).rev() {
+                    quotient[i - chunk_length] = remainder[i];
+                    let carry = remainder[i] * s;
+                    remainder[i - chunk_length] += carry;
+                }
+                let expected = G1Projective::msm(&kzg.g1[..quotient.len()], &quotient)
+                    .unwrap()
+                    .into_affine();
+                assert_eq!(
+                    *proof, expected,
+                    "chunk length {}, coset {}",
+                    chunk_length, k
+                );
+            }
+        }
+
+        assert_eq!(
+            kzg.compute_multiproofs(&input_poly, 3),
+            Err(KzgError::InvalidDomainSize(3))
+        );
+        assert_eq!(
+            kzg.compute_multiproofs(&input_poly, 128),
+            Err(KzgError::TooLong {
+                max: input_poly.len(),
+                actual: 128
+            })
+        );
+    }
+
+    #[test]
+    fn test_frames() {
+        use crate::consts::GETTYSBURG_ADDRESS_BYTES;
+        use crate::encoding::reverse_bits;
+
+        let mut kzg = KZG_3000.clone();
+        let blob = Blob::from_bytes_and_pad(GETTYSBURG_ADDRESS_BYTES);
+        let polynomial = blob.to_polynomial().unwrap();
+        let commitment = kzg.commit(&polynomial).unwrap();
+
+        let params = EncodingParams::new(16, 8).unwrap();
+        let frames = kzg.compute_frames(&polynomial, &params).unwrap();
+        let chunks = encoding::encode_chunks(&polynomial, &params).unwrap();
+        assert_eq!(frames.len(), 8);
+
+        kzg.set_encoding_params(params).unwrap();
+        for (i, frame) in frames.iter().enumerate() {
+            let index = i as u64;
+            assert_eq!(
+                kzg.verify_frame(commitment, frame, index, &params),
+                Ok(true)
+            );
+            assert_eq!(frame.evaluations(index, &params).as_ref(), Ok(&chunks[i]));
+            assert_eq!(
+                kzg.compute_coset_proof(&polynomial, reverse_bits(index, 8)),
+                Ok(frame.proof)
+            );
+
+            let wrong_index = (index + 1) % 8;
+            assert_eq!(
+                kzg.verify_frame(commitment, frame, wrong_index, &params),
+                Ok(false)
+            );
+            let mut tampered = frame.clone();
+            tampered.coeffs[0] += Fr::one();
+            assert_eq!(
+                kzg.verify_frame(commitment, &tampered, index, &params),
+                Ok(false)
+            );
+
+            // what goes over the wire is what is checked
+            let received = Frame::from_bytes(&frame.to_bytes()).unwrap();
+            assert_eq!(
+                kzg.verify_frame(commitment, &received, index, &params),
+                Ok(true)
+            );
+        }
+
+        let mut short = frames[0].clone();
+        short.coeffs.pop();
+        assert_eq!(
+            kzg.verify_frame(commitment, &short, 0, &params),
+            Err(KzgError::LengthMismatch {
+                expected: 16,
+                actual: 15
+            })
+        );
+        assert_eq!(
+            kzg.verify_frame(commitment, &frames[0], 8, &params),
+            Err(KzgError::IndexOutOfRange { index: 8, len: 8 })
+        );
+    }
+
+    #[test]
+    fn test_coset_proofs() {
+        use crate::consts::GETTYSBURG_ADDRESS_BYTES;
+
+        let mut kzg = KZG_INSTANCE.clone();
+        let input = Blob::from_bytes_and_pad(GETTYSBURG_ADDRESS_BYTES);
+        let input_poly = input.to_polynomial().unwrap();
+        let commitment = kzg.commit(&input_poly).unwrap();
+        let domain = GeneralEvaluationDomain::<Fr>::new(input_poly.len()).unwrap();
+        let coeffs = domain.ifft(&input_poly.to_vec());
+
+        assert_eq!(
+            kzg.compute_coset_proof(&input_poly, 0),
+            Err(KzgError::SetupIncomplete)
+        );
+
+        // 16 x 4 covers the polynomial exactly, 8 x 16 extends it to twice its size
+        for (chunk_length, num_chunks) in [(16, 4), (8, 16)] {
+            kzg.data_setup_mins(chunk_length, num_chunks).unwrap();
+            let num_evaluations = (chunk_length * num_chunks) as usize;
+            let eval_domain = GeneralEvaluationDomain::<Fr>::new(num_evaluations).unwrap();
+            let evaluations = eval_domain.fft(&coeffs);
+
+            for chunk_index in 0..num_chunks {
+                let proof = kzg.compute_coset_proof(&input_poly, chunk_index).unwrap();
+                let coset_shift = eval_domain.element(chunk_index as usize);
+                let mut chunk: Vec<Fr> = evaluations
+                    .iter()
+                    .skip(chunk_index as usize)
+                    .step_by(num_chunks as usize)
+                    .copied()
+                    .collect();
+                assert_eq!(
+                    kzg.verify_coset_proof(commitment, proof, coset_shift, &chunk),
+                    Ok(true)
+                );
+
+                chunk[1] += Fr::one();
+                assert_eq!(
+                    kzg.verify_coset_proof(commitment, proof, coset_shift, &chunk),
+                    Ok(false)
+                );
+            }
+        }
+
+        // without extension the chunk proofs are the fk20 multiproofs
+        kzg.data_setup_mins(16, 4).unwrap();
+        let multiproofs = kzg.compute_multiproofs(&input_poly, 16).unwrap();
+        for (chunk_index, multiproof) in multiproofs.iter().enumerate() {
+            assert_eq!(
+                kzg.compute_coset_proof(&input_poly, chunk_index as u64),
+                Ok(*multiproof)
+            );
+        }
+
+        assert_eq!(
+            kzg.compute_coset_proof(&input_poly, 4),
+            Err(KzgError::IndexOutOfRange { index: 4, len: 4 })
+        );
+        assert_eq!(
+            kzg.verify_coset_proof(commitment, commitment, Fr::one(), &[Fr::one(); 3]),
+            Err(KzgError::InvalidDomainSize(3))
+        );
+    }
+
+    #[test]
+    fn test_length_proof() {
+        use crate::consts::GETTYSBURG_ADDRESS_BYTES;
+
+        let kzg = KZG_INSTANCE.clone();
+        let input = Blob::from_bytes_and_pad(GETTYSBURG_ADDRESS_BYTES);
+        let input_poly = input.to_polynomial().unwrap();
+        let n = input_poly.len() as u64;
+
+        let length_commitment = kzg.commit_g2(&input_poly).unwrap();
+        let length_proof = kzg.compute_length_proof(&input_poly).unwrap();
+        assert_eq!(
+            kzg.verify_length_proof(length_commitment, length_proof, n),
+            Ok(true)
+        );
+        assert_eq!(
+            kzg.verify_length_proof(length_commitment, length_proof, n / 2),
+            Ok(false)
+        );
+        assert_eq!(
+            kzg.verify_length_proof(length_commitment, length_proof, n * 2),
+            Ok(false)
+        );
+
+        // the g2 commitment matches the g1 one
+        let commitment = kzg.commit(&input_poly).unwrap();
+        assert!(kzg.verify_commitment_equivalence(commitment, length_commitment));
+
+        assert_eq!(
+            kzg.verify_length_proof(length_commitment, length_proof, kzg.srs_order + 1),
+            Err(KzgError::SrsTooSmall {
+                required: kzg.srs_order + 1,
+                available: kzg.srs_order
+            })
+        );
+        let mut tau_only = kzg.clone();
+        tau_only.g2 = vec![kzg.g2[1]];
+        tau_only.g2_layout = G2Layout::TauOnly;
+        assert_eq!(
+            tau_only.commit_g2(&input_poly),
+            Err(KzgError::G2PowerUnavailable(2))
+        );
+    }
+
+    #[test]
+    fn test_verify_commitment_equivalence() {
+        use crate::consts::GETTYSBURG_ADDRESS_BYTES;
+
+        let kzg = KZG_INSTANCE.clone();
+        let mut g1_commitments = vec![];
+        let mut g2_commitments = vec![];
+        for start in 0..4 {
+            let input = Blob::from_bytes_and_pad(&GETTYSBURG_ADDRESS_BYTES[start * 10..]);
+            let input_poly = input.to_polynomial().unwrap();
+            g1_commitments.push(kzg.commit(&input_poly).unwrap());
+            g2_commitments.push(kzg.commit_g2(&input_poly).unwrap());
+        }
+
+        assert!(kzg.verify_commitment_equivalence(g1_commitments[0], g2_commitments[0]));
+        assert!(!kzg.verify_commitment_equivalence(g1_commitments[0], g2_commitments[1]));
+        assert_eq!(
+            kzg.verify_commitment_equivalence_batch(&g1_commitments, &g2_commitments),
+            Ok(true)
+        );
+        assert_eq!(kzg.verify_commitment_equivalence_batch(&[], &[]), Ok(true));
+
+        // swapped pairs keep the plain sums equal but not the weighted ones
+        let mut swapped = g2_commitments.clone();
+        swapped.swap(1, 2);
+        assert_eq!(
+            kzg.verify_commitment_equivalence_batch(&g1_commitments, &swapped),
+            Ok(false)
+        );
+        assert_eq!(
+            kzg.verify_commitment_equivalence_batch(&g1_commitments, &g2_commitments[1..]),
+            Err(KzgError::LengthMismatch {
+                expected: g1_commitments.len(),
+                actual: g2_commitments.len() - 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_g2_layouts() {
+        use crate::consts::GETTYSBURG_ADDRESS_BYTES;
+
+        let mut full = KZG_INSTANCE.clone();
+        let mut powers_of_two = full.clone();
+        powers_of_two.g2 = (0..12).map(|k| full.g2[1 << k]).collect();
+        powers_of_two.g2_layout = G2Layout::PowersOfTwo;
+        let mut tau_only = full.clone();
+        tau_only.g2 = vec![full.g2[1]];
+        tau_only.g2_layout = G2Layout::TauOnly;
+
+        assert_eq!(full.g2_tau_pow(0), Ok(G2Affine::generator()));
+        assert_eq!(full.g2_tau_pow(5), Ok(full.g2[5]));
+        assert_eq!(
+            full.g2_tau_pow(3000),
+            Err(KzgError::G2PowerUnavailable(3000))
+        );
+        assert_eq!(powers_of_two.g2_tau_pow(1), Ok(full.g2[1]));
+        assert_eq!(powers_of_two.g2_tau_pow(1024), Ok(full.g2[1024]));
+        assert_eq!(
+            powers_of_two.g2_tau_pow(3),
+            Err(KzgError::G2PowerUnavailable(3))
+        );
+        assert_eq!(
+            powers_of_two.g2_tau_pow(4096),
+            Err(KzgError::G2PowerUnavailable(4096))
+        );
+        assert_eq!(tau_only.g2_tau_pow(1), Ok(full.g2[1]));
+        assert_eq!(tau_only.g2_tau_pow(2), Err(KzgError::G2PowerUnavailable(2)));
+
+        let input = Blob::from_bytes_and_pad(GETTYSBURG_ADDRESS_BYTES);
+        let input_poly = input.to_polynomial().unwrap();
+        let commitment = full.commit(&input_poly).unwrap();
+        full.data_setup_mins(16, 4).unwrap();
+        let proof = full.compute_coset_proof(&input_poly, 1).unwrap();
+        let coset_shift = Kzg::get_nth_root_of_unity(input_poly.len(), 1).unwrap();
+        let chunk: Vec<Fr> = input_poly.to_vec().into_iter().skip(1).step_by(4).collect();
+
+        // single openings work with every layout, coset proofs need [tau^16]_2
+        let (opening, value_fr) = full.compute_kzg_proof_at(&input_poly, coset_shift).unwrap();
+        for kzg in [&full, &powers_of_two, &tau_only] {
+            assert!(kzg.verify_kzg_proof(commitment, opening, value_fr, coset_shift));
+        }
+        for kzg in [&full, &powers_of_two] {
+            assert_eq!(
+                kzg.verify_coset_proof(commitment, proof, coset_shift, &chunk),
+                Ok(true)
+            );
+        }
+        assert_eq!(
+            tau_only.verify_coset_proof(commitment, proof, coset_shift, &chunk),
+            Err(KzgError::G2PowerUnavailable(16))
+        );
+
+        let mut bytes = vec![];
+        tau_only.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(
+            Kzg::deserialize_compressed(bytes.as_slice()).unwrap(),
+            tau_only
+        );
+    }
+
+    #[test]
+    fn test_precomputed_msm_tables() {
+        use crate::consts::GETTYSBURG_ADDRESS_BYTES;
+
+        let kzg = KZG_INSTANCE.clone();
+        let input = Blob::from_bytes_and_pad(GETTYSBURG_ADDRESS_BYTES);
+        let input_poly = input.to_polynomial().unwrap();
+        let commitment = kzg.commit(&input_poly).unwrap();
+        let proof = kzg
+            .compute_kzg_proof_with_roots_of_unity(&input_poly, 5)
+            .unwrap();
+
+        kzg.precompute_msm_tables(&[input_poly.len()], 1 << 20)
+            .unwrap();
+        assert!(kzg.lagrange_cache.get_tables(input_poly.len()).is_some());
+        assert_eq!(kzg.commit(&input_poly), Ok(commitment));
+        assert_eq!(
+            kzg.compute_kzg_proof_with_roots_of_unity(&input_poly, 5),
+            Ok(proof)
+        );
+    }
+
+    #[test]
+    fn test_compute_quotient_eval() {
+        use ark_std::UniformRand;
+
+        let rng = &mut rand::thread_rng();
+        let domain = Domain::get(32).unwrap();
+        let eval_fr: Vec<Fr> = (0..32).map(|_| Fr::rand(rng)).collect();
+
+        for index in [0, 9, 31] {
+            // the cached domain and a plain batch inversion give the same quotient
+            let z_fr = domain.roots()[index];
+            let mut inverses: Vec<Fr> = domain.roots().iter().map(|root| *root - z_fr).collect();
+            batch_inversion(&mut inverses);
+            let from_batch = Kzg::compute_quotient_eval(
+                &eval_fr,
+                eval_fr[index],
+                domain.roots(),
+                &inverses,
+                Some((index, z_fr.inverse().unwrap())),
+            );
+            let from_tables = Kzg::compute_quotient_eval(
+                &eval_fr,
+                eval_fr[index],
+                domain.roots(),
+                &domain.inverse_differences(index),
+                Some((index, domain.inverse_root(index))),
+            );
+            assert_eq!(from_batch, from_tables);
+
+            // q(X) * (X - z) = p(X) - p(z) holds as polynomials, check it at a random point
+            let coefficient_domain = GeneralEvaluationDomain::<Fr>::new(32).unwrap();
+            let x = Fr::rand(rng);
+            let evaluate = |evals: &[Fr]| evaluate_coeffs(&coefficient_domain.ifft(evals), x);
+            assert_eq!(
+                evaluate(&from_tables) * (x - z_fr),
+                evaluate(&eval_fr) - eval_fr[index]
+            );
+        }
+    }
 }
@@ -0,0 +1,306 @@
+//! Cache of the g1 srs in lagrange form, keyed by the size of the domain.
+//!
+//! Committing to evaluations needs the lagrange bases of the domain, which cost a
+//! group IFFT to derive from the srs. The cache keeps every size that has been
+//! derived once so a commitment is a single MSM, and can be written to disk so
+//! the IFFTs are not redone on every start. Fixed-base MSM tables built over
+//! the bases are kept alongside them, but never written to disk.
+
+use crate::errors::KzgError;
+use crate::msm::PrecomputedBases;
+use crate::srs::{self, PointValidation};
+use crate::traits::PointCodec;
+use ark_bn254::G1Affine;
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+    Write,
+};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+/// Thread-safe map from domain size to the lagrange bases of that domain.
+///
+/// The cache is derived data: two caches always compare equal, and it serializes
+/// to nothing along with the `Kzg` holding it.
+#[derive(Default)]
+pub struct LagrangeCache {
+    bases: RwLock<HashMap<usize, Arc<Vec<G1Affine>>>>,
+    tables: RwLock<HashMap<usize, Arc<PrecomputedBases>>>,
+}
+
+impl LagrangeCache {
+    pub fn get(&self, size: usize) -> Option<Arc<Vec<G1Affine>>> {
+        let bases = self.bases.read().unwrap_or_else(|e| e.into_inner());
+        bases.get(&size).cloned()
+    }
+
+    /// stores the bases under their length, keeping the existing entry if another
+    /// thread got there first
+    pub(crate) fn insert(&self, bases: Vec<G1Affine>) -> Arc<Vec<G1Affine>> {
+        let mut cached = self.bases.write().unwrap_or_else(|e| e.into_inner());
+        cached
+            .entry(bases.len())
+            .or_insert_with(|| Arc::new(bases))
+            .clone()
+    }
+
+    /// the cached domain sizes, smallest first
+    pub fn sizes(&self) -> Vec<usize> {
+        let bases = self.bases.read().unwrap_or_else(|e| e.into_inner());
+        let mut sizes: Vec<usize> = bases.keys().copied().collect();
+        sizes.sort_unstable();
+        sizes
+    }
+
+    /// fixed-base MSM tables over the bases of that size, if they were built
+    pub fn get_tables(&self, size: usize) -> Option<Arc<PrecomputedBases>> {
+        let tables = self.tables.read().unwrap_or_else(|e| e.into_inner());
+        tables.get(&size).cloned()
+    }
+
+    /// stores the tables under the number of bases they were built from,
+    /// replacing older ones of that size
+    pub(crate) fn insert_tables(&self, tables: PrecomputedBases) -> Arc<PrecomputedBases> {
+        let tables = Arc::new(tables);
+        self.tables
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(tables.len(), tables.clone());
+        tables
+    }
+
+    pub fn clear(&self) {
+        self.bases
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+        self.tables
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+
+    /// Writes every cached size as a big-endian u64 size followed by the
+    /// compressed big-endian bases, the same point encoding as the srs files.
+    pub fn write_to<W: std::io::Write>(&self, mut writer: W) -> Result<(), KzgError> {
+        for size in self.sizes() {
+            let bases = self.get(size).unwrap_or_default();
+            let mut bytes = Vec::with_capacity(8 + size * G1Affine::COMPRESSED_SIZE);
+            bytes.extend((size as u64).to_be_bytes());
+            for point in bases.iter() {
+                bytes.extend(point.to_compressed_be());
+            }
+            writer.write_all(&bytes)?;
+        }
+        Ok(())
+    }
+
+    /// Reads back what `write_to` wrote. The bases are returned rather than cached,
+    /// since nothing here can tell which srs they were derived from.
+    pub fn read_from<R: std::io::Read>(
+        mut reader: R,
+        validation: PointValidation,
+    ) -> Result<Vec<Vec<G1Affine>>, KzgError> {
+        let mut all_bases = vec![];
+        loop {
+            let mut size_bytes = [0u8; 8];
+            let read = read_up_to(&mut reader, &mut size_bytes)?;
+            if read == 0 {
+                return Ok(all_bases);
+            }
+            if read < size_bytes.len() {
+                return Err(KzgError::TruncatedInput("size"));
+            }
+            let size = usize::try_from(u64::from_be_bytes(size_bytes))
+                .map_err(|e| KzgError::Serialization(e.to_string()))?;
+            let bases =
+                srs::read_points(&mut reader, Some(size), validation, None).map_err(|source| {
+                    KzgError::LagrangeBases {
+                        size,
+                        source: Box::new(source),
+                    }
+                })?;
+            all_bases.push(bases);
+        }
+    }
+}
+
+/// like `Read::read_exact`, but an empty reader is not an error
+fn read_up_to<R: std::io::Read>(reader: &mut R, buffer: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..])? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    Ok(filled)
+}
+
+impl Clone for LagrangeCache {
+    fn clone(&self) -> Self {
+        let bases = self.bases.read().unwrap_or_else(|e| e.into_inner());
+        let tables = self.tables.read().unwrap_or_else(|e| e.into_inner());
+        Self {
+            bases: RwLock::new(bases.clone()),
+            tables: RwLock::new(tables.clone()),
+        }
+    }
+}
+
+impl PartialEq for LagrangeCache {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl fmt::Debug for LagrangeCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tables = self.tables.read().unwrap_or_else(|e| e.into_inner());
+        let mut table_sizes: Vec<usize> = tables.keys().copied().collect();
+        table_sizes.sort_unstable();
+        f.debug_struct("LagrangeCache")
+            .field("sizes", &self.sizes())
+            .field("table_sizes", &table_sizes)
+            .finish()
+    }
+}
+
+impl CanonicalSerialize for LagrangeCache {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        _writer: W,
+        _compress: Compress,
+    ) -> Result<(), SerializationError> {
+        Ok(())
+    }
+
+    fn serialized_size(&self, _compress: Compress) -> usize {
+        0
+    }
+}
+
+impl Valid for LagrangeCache {
+    fn check(&self) -> Result<(), SerializationError> {
+        Ok(())
+    }
+}
+
+impl CanonicalDeserialize for LagrangeCache {
+    fn deserialize_with_mode<R: Read>(
+        _reader: R,
+        _compress: Compress,
+        _validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        Ok(Self::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Domain;
+    use crate::kzg::Kzg;
+    use ark_bn254::{Fq, Fr};
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_std::str::FromStr;
+    use lazy_static::lazy_static;
+    use std::fs;
+
+    lazy_static! {
+        static ref KZG_3000: Kzg = Kzg::setup(true).unwrap();
+    }
+
+    #[test]
+    fn test_matches_fixture() {
+        let fixture: Vec<G1Affine> = fs::read_to_string("src/test-files/lagrangeG1SRS.txt")
+            .unwrap()
+            .lines()
+            .map(|line| {
+                let (x, y) = line.trim().split_once(',').unwrap();
+                G1Affine::new(Fq::from_str(x).unwrap(), Fq::from_str(y).unwrap())
+            })
+            .collect();
+
+        let kzg = KZG_3000.clone();
+        let bases = kzg.g1_lagrange(64).unwrap();
+        assert_eq!(*bases, fixture);
+        assert!(Arc::ptr_eq(&bases, &kzg.g1_lagrange(64).unwrap()));
+        assert_eq!(kzg.lagrange_cache.sizes(), vec![64]);
+    }
+
+    #[test]
+    fn test_concurrent_access() {
+        let kzg = KZG_3000.clone();
+        let expected = kzg.g1_ifft(128).unwrap();
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| assert_eq!(*kzg.g1_lagrange(128).unwrap(), expected));
+            }
+        });
+        assert_eq!(kzg.lagrange_cache.sizes(), vec![128]);
+    }
+
+    #[test]
+    fn test_persist_and_load() {
+        let kzg = KZG_3000.clone();
+        kzg.precompute_lagrange_bases(&[8, 32]).unwrap();
+        assert_eq!(kzg.lagrange_cache.sizes(), vec![8, 32]);
+
+        let path = std::env::temp_dir().join(format!("lagrange-{}.bin", std::process::id()));
+        let path = path.to_str().unwrap();
+        kzg.save_lagrange_bases(path).unwrap();
+
+        let fresh = KZG_3000.clone();
+        fresh
+            .load_lagrange_bases(path, PointValidation::Full)
+            .unwrap();
+        assert_eq!(fresh.lagrange_cache.sizes(), vec![8, 32]);
+        assert_eq!(*fresh.g1_lagrange(32).unwrap(), kzg.g1_ifft(32).unwrap());
+
+        // bases derived from another srs are rejected
+        let mut other = KZG_3000.clone();
+        let mut g1 = other.get_g1_points();
+        g1.swap(1, 2);
+        other.set_g1_points(g1);
+        assert_eq!(
+            other.load_lagrange_bases(path, PointValidation::Trusted),
+            Err(KzgError::LagrangeBasesMismatch(8))
+        );
+        assert!(other.lagrange_cache.sizes().is_empty());
+
+        // tampering that keeps sum w^i * L_i unchanged is still caught
+        let mut tampered = kzg.g1_ifft(8).unwrap();
+        let w = Domain::get(8).unwrap().generator();
+        let shift = (G1Affine::generator() * Fr::from(7u64)).into_affine();
+        tampered[0] = (tampered[0] + shift * w).into_affine();
+        tampered[1] = (tampered[1] - shift).into_affine();
+        let cache = LagrangeCache::default();
+        cache.insert(tampered);
+        let mut tampered_bytes = Vec::new();
+        cache.write_to(&mut tampered_bytes).unwrap();
+        let tampered_path = format!("{}.tampered", path);
+        fs::write(&tampered_path, tampered_bytes).unwrap();
+        let fresh = KZG_3000.clone();
+        assert_eq!(
+            fresh.load_lagrange_bases(&tampered_path, PointValidation::Full),
+            Err(KzgError::LagrangeBasesMismatch(8))
+        );
+        fs::remove_file(&tampered_path).unwrap();
+
+        let bytes = fs::read(path).unwrap();
+        fs::remove_file(path).unwrap();
+        assert_eq!(
+            LagrangeCache::read_from(&bytes[..bytes.len() - 1], PointValidation::Full),
+            Err(KzgError::LagrangeBases {
+                size: 32,
+                source: Box::new(KzgError::TruncatedInput("point"))
+            })
+        );
+        assert_eq!(
+            LagrangeCache::read_from(&bytes[..4], PointValidation::Full),
+            Err(KzgError::TruncatedInput("size"))
+        );
+    }
+}
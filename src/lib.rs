@@ -1,8 +1,16 @@
+#![cfg_attr(test, allow(clippy::bool_assert_comparison))]
+
 mod arith;
 pub mod blob;
+pub mod codec;
 mod consts;
+pub mod domain;
+pub mod encoding;
 pub mod errors;
 pub mod helpers;
 pub mod kzg;
+pub mod lagrange;
+pub mod msm;
 pub mod polynomial;
-mod traits;
+pub mod srs;
+pub mod traits;
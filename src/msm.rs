@@ -0,0 +1,271 @@
+//! Fixed-base multi-scalar multiplication over precomputed tables.
+//!
+//! When the same bases are used for every MSM, as the lagrange bases are for
+//! every commitment, the doublings between the windows of a Pippenger MSM can
+//! be done once up front: every base `P` is stored as `2^(c * j) * P` for every
+//! window `j`. An MSM is then a single pass of bucket additions over all the
+//! windows at once, with no doublings at all.
+//!
+//! Every MSM also allocates `2^window_bits - 1` projective buckets per chunk of
+//! scalars summed concurrently, one chunk per rayon thread with `parallel` and a
+//! single one without. The memory budget covers the tables and these buckets.
+
+use crate::errors::KzgError;
+use ark_bn254::{Fr, G1Affine, G1Projective};
+use ark_ec::{AffineRepr, CurveGroup, Group};
+use ark_ff::PrimeField;
+use ark_std::Zero;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Largest window tried when fitting the tables in a memory budget.
+const MAX_WINDOW_BITS: usize = 20;
+
+/// Bases of a fixed-base MSM, with the shifted copies of every base for every window.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PrecomputedBases {
+    /// `tables[i * num_windows + j] = 2^(window_bits * j) * bases[i]`
+    tables: Vec<G1Affine>,
+    window_bits: usize,
+    num_windows: usize,
+    /// number of chunks of scalars summed concurrently, each with its own buckets
+    num_chunks: usize,
+}
+
+impl PrecomputedBases {
+    /// Builds the tables with the window that needs the fewest additions per MSM
+    /// while keeping the tables and the buckets of an MSM within `memory_budget`
+    /// bytes. A wider window needs fewer shifted copies of the bases but more buckets.
+    pub fn new(bases: &[G1Affine], memory_budget: usize) -> Result<Self, KzgError> {
+        let num_chunks = concurrent_chunks(bases.len());
+        let window_bits = Self::window_for_budget(bases.len(), num_chunks, memory_budget)
+            .ok_or(KzgError::MemoryBudgetTooSmall(memory_budget))?;
+        Ok(Self::with_window(bases, window_bits))
+    }
+
+    /// Builds the tables with a fixed window of `window_bits` bits.
+    pub fn with_window(bases: &[G1Affine], window_bits: usize) -> Self {
+        let window_bits = window_bits.clamp(1, MAX_WINDOW_BITS);
+        let num_windows = Self::num_windows(window_bits);
+
+        let mut tables = Vec::with_capacity(bases.len() * num_windows);
+        for base in bases {
+            let mut shifted = base.into_group();
+            for _ in 0..num_windows {
+                tables.push(shifted);
+                for _ in 0..window_bits {
+                    shifted.double_in_place();
+                }
+            }
+        }
+
+        Self {
+            tables: G1Projective::normalize_batch(&tables),
+            window_bits,
+            num_windows,
+            num_chunks: concurrent_chunks(bases.len()),
+        }
+    }
+
+    /// number of bases the tables were built from
+    pub fn len(&self) -> usize {
+        self.tables.len() / self.num_windows
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tables.is_empty()
+    }
+
+    pub fn window_bits(&self) -> usize {
+        self.window_bits
+    }
+
+    /// memory taken by the tables in bytes
+    pub fn memory_size(&self) -> usize {
+        self.tables.len() * std::mem::size_of::<G1Affine>()
+    }
+
+    /// memory allocated for the buckets by every call to `msm`, in bytes
+    pub fn bucket_memory_size(&self) -> usize {
+        Self::bucket_size(self.window_bits, self.num_chunks)
+    }
+
+    /// `sum scalars[i] * bases[i]`, needs exactly one scalar per base
+    pub fn msm(&self, scalars: &[Fr]) -> Result<G1Projective, KzgError> {
+        if scalars.len() != self.len() {
+            return Err(KzgError::LengthMismatch {
+                expected: self.len(),
+                actual: scalars.len(),
+            });
+        }
+        Ok(self.bucket_sums(scalars))
+    }
+
+    #[cfg(feature = "parallel")]
+    fn bucket_sums(&self, scalars: &[Fr]) -> G1Projective {
+        let chunk_size = scalars.len().div_ceil(self.num_chunks).max(1);
+        scalars
+            .par_chunks(chunk_size)
+            .enumerate()
+            .map(|(i, chunk)| self.bucket_sum(chunk, i * chunk_size))
+            .sum()
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    fn bucket_sums(&self, scalars: &[Fr]) -> G1Projective {
+        self.bucket_sum(scalars, 0)
+    }
+
+    /// Adds the table entry of every non zero digit into the bucket of that digit,
+    /// then sums `digit * bucket` with a running sum. `first` is the index of the
+    /// base of `scalars[0]`.
+    fn bucket_sum(&self, scalars: &[Fr], first: usize) -> G1Projective {
+        let mut buckets = vec![G1Projective::zero(); (1 << self.window_bits) - 1];
+        for (i, scalar) in scalars.iter().enumerate() {
+            let limbs = scalar.into_bigint();
+            let shifted = &self.tables[(first + i) * self.num_windows..];
+            for (window, point) in shifted.iter().take(self.num_windows).enumerate() {
+                let digit =
+                    window_digit(limbs.as_ref(), window * self.window_bits, self.window_bits);
+                if digit != 0 {
+                    buckets[digit - 1] += point;
+                }
+            }
+        }
+
+        let mut running_sum = G1Projective::zero();
+        let mut result = G1Projective::zero();
+        for bucket in buckets.iter().rev() {
+            running_sum += bucket;
+            result += running_sum;
+        }
+        result
+    }
+
+    fn num_windows(window_bits: usize) -> usize {
+        (Fr::MODULUS_BIT_SIZE as usize).div_ceil(window_bits)
+    }
+
+    fn bucket_size(window_bits: usize, num_chunks: usize) -> usize {
+        num_chunks * ((1 << window_bits) - 1) * std::mem::size_of::<G1Projective>()
+    }
+
+    /// the window with the fewest additions, about
+    /// `n * windows + chunks * 2 * 2^window_bits`, among the ones whose tables and
+    /// buckets fit in the budget
+    fn window_for_budget(
+        num_bases: usize,
+        num_chunks: usize,
+        memory_budget: usize,
+    ) -> Option<usize> {
+        (1..=MAX_WINDOW_BITS)
+            .filter(|&bits| {
+                let tables = num_bases * Self::num_windows(bits) * std::mem::size_of::<G1Affine>();
+                tables.saturating_add(Self::bucket_size(bits, num_chunks)) <= memory_budget
+            })
+            .min_by_key(|&bits| num_bases * Self::num_windows(bits) + num_chunks * (2 << bits))
+    }
+}
+
+/// number of chunks the scalars of an MSM over `num_bases` bases are split into
+#[cfg(feature = "parallel")]
+fn concurrent_chunks(num_bases: usize) -> usize {
+    rayon::current_num_threads().clamp(1, num_bases.max(1))
+}
+
+#[cfg(not(feature = "parallel"))]
+fn concurrent_chunks(_num_bases: usize) -> usize {
+    1
+}
+
+/// reads `bits` bits of a little-endian limb array starting at bit `start`
+fn window_digit(limbs: &[u64], start: usize, bits: usize) -> usize {
+    let limb = start / 64;
+    let offset = start % 64;
+    if limb >= limbs.len() {
+        return 0;
+    }
+    let mut digit = limbs[limb] >> offset;
+    if offset + bits > 64 && limb + 1 < limbs.len() {
+        digit |= limbs[limb + 1] << (64 - offset);
+    }
+    (digit & ((1 << bits) - 1)) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ec::VariableBaseMSM;
+    use ark_std::UniformRand;
+
+    #[test]
+    fn test_matches_variable_base_msm() {
+        let rng = &mut rand::thread_rng();
+        let bases: Vec<G1Affine> = (0..100).map(|_| G1Affine::rand(rng)).collect();
+        let mut scalars: Vec<Fr> = (0..100).map(|_| Fr::rand(rng)).collect();
+        scalars[3] = Fr::zero();
+        scalars[7] = -Fr::from(1u64);
+        let expected = G1Projective::msm(&bases, &scalars).unwrap();
+
+        for window_bits in [1, 4, 7, 13, MAX_WINDOW_BITS] {
+            let precomputed = PrecomputedBases::with_window(&bases, window_bits);
+            assert_eq!(precomputed.len(), 100);
+            assert_eq!(
+                precomputed.msm(&scalars),
+                Ok(expected),
+                "window {}",
+                window_bits
+            );
+        }
+    }
+
+    #[test]
+    fn test_memory_budget() {
+        let rng = &mut rand::thread_rng();
+        let bases: Vec<G1Affine> = (0..64).map(|_| G1Affine::rand(rng)).collect();
+
+        let scalars: Vec<Fr> = (0..64).map(|_| Fr::rand(rng)).collect();
+        let total_size =
+            |tables: &PrecomputedBases| tables.memory_size() + tables.bucket_memory_size();
+
+        let unbounded = PrecomputedBases::new(&bases, usize::MAX).unwrap();
+        let expected = unbounded.msm(&scalars).unwrap();
+        for window_bits in 1..=12 {
+            // the budget of any window fits the tables and the buckets
+            let budget = total_size(&PrecomputedBases::with_window(&bases, window_bits));
+            let bounded = PrecomputedBases::new(&bases, budget).unwrap();
+            assert!(total_size(&bounded) <= budget, "window {}", window_bits);
+            if budget < total_size(&unbounded) {
+                assert_ne!(bounded.window_bits(), unbounded.window_bits());
+            }
+            assert_eq!(bounded.msm(&scalars), Ok(expected));
+        }
+        assert_eq!(
+            PrecomputedBases::with_window(&bases, 12).bucket_memory_size(),
+            concurrent_chunks(64) * 4095 * std::mem::size_of::<G1Projective>()
+        );
+
+        assert_eq!(
+            PrecomputedBases::new(&bases, 1000),
+            Err(KzgError::MemoryBudgetTooSmall(1000))
+        );
+        assert_eq!(
+            unbounded.msm(&scalars[1..]),
+            Err(KzgError::LengthMismatch {
+                expected: 64,
+                actual: 63
+            })
+        );
+    }
+
+    #[test]
+    fn test_window_digit() {
+        let limbs = [u64::MAX, 0b1011, 0, 1 << 63];
+        assert_eq!(window_digit(&limbs, 0, 4), 0b1111);
+        assert_eq!(window_digit(&limbs, 62, 4), 0b1111);
+        assert_eq!(window_digit(&limbs, 63, 4), 0b0111);
+        assert_eq!(window_digit(&limbs, 64, 3), 0b011);
+        assert_eq!(window_digit(&limbs, 252, 8), 0b1000);
+        assert_eq!(window_digit(&limbs, 256, 8), 0);
+    }
+}
@@ -1,21 +1,45 @@
-use crate::{errors::PolynomialError, helpers};
+use crate::{
+    domain::Domain,
+    errors::{KzgError, PolynomialError},
+    helpers,
+};
 use ark_bn254::Fr;
 use ark_std::Zero;
 
+/// How the elements of a polynomial are read, `PolynomialForm` in EigenDA v2.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PolynomialForm {
+    /// the elements are the evaluations on the domain of their size, `p(w^i)`
+    #[default]
+    Eval,
+    /// the elements are the coefficients, `p(X) = sum elements[i] * X^i`
+    Coeff,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct Polynomial {
     elements: Vec<Fr>,
     length_of_padded_blob: usize,
     length_of_padded_blob_as_fr_vector: usize,
+    form: PolynomialForm,
 }
 
 impl Polynomial {
-    /// Constructs a new `Polynomial` with a given vector of `Fr` elements.
-    pub fn new(elements: &Vec<Fr>, length_of_padded_blob: usize) -> Result<Self, PolynomialError> {
+    /// Constructs a new `Polynomial` in evaluation form with a given vector of
+    /// `Fr` elements.
+    pub fn new(elements: &[Fr], length_of_padded_blob: usize) -> Result<Self, PolynomialError> {
+        Self::new_with_form(elements, length_of_padded_blob, PolynomialForm::Eval)
+    }
+
+    /// Constructs a new `Polynomial` whose elements are read in the given form.
+    /// Either way they are padded with zeros up to a power of 2.
+    pub fn new_with_form(
+        elements: &[Fr],
+        length_of_padded_blob: usize,
+        form: PolynomialForm,
+    ) -> Result<Self, PolynomialError> {
         if elements.is_empty() {
-            return Err(PolynomialError::GenericError(
-                "elements are empty".to_string(),
-            ));
+            return Err(PolynomialError::EmptyElements);
         }
         let mut padded_input_fr = vec![];
         for i in 0..elements.len().next_power_of_two() {
@@ -29,6 +53,40 @@ impl Polynomial {
             elements: padded_input_fr,
             length_of_padded_blob,
             length_of_padded_blob_as_fr_vector: elements.len(),
+            form,
+        })
+    }
+
+    pub fn form(&self) -> PolynomialForm {
+        self.form
+    }
+
+    /// The same polynomial in coefficient form, with an IFFT over the domain of
+    /// its size when it holds evaluations.
+    pub fn to_coeff_form(&self) -> Result<Self, KzgError> {
+        self.to_form(PolynomialForm::Coeff)
+    }
+
+    /// The same polynomial in evaluation form, with an FFT over the domain of its
+    /// size when it holds coefficients.
+    pub fn to_eval_form(&self) -> Result<Self, KzgError> {
+        self.to_form(PolynomialForm::Eval)
+    }
+
+    fn to_form(&self, form: PolynomialForm) -> Result<Self, KzgError> {
+        let elements = match (self.form, form) {
+            (PolynomialForm::Eval, PolynomialForm::Coeff) => {
+                Domain::get(self.len())?.ifft(&self.elements)?
+            }
+            (PolynomialForm::Coeff, PolynomialForm::Eval) => {
+                Domain::get(self.len())?.fft(&self.elements)?
+            }
+            _ => self.elements.clone(),
+        };
+        Ok(Polynomial {
+            elements,
+            form,
+            ..self.clone()
         })
     }
 
@@ -61,6 +119,15 @@ impl Polynomial {
     }
 }
 
+/// `sum coeffs[i] * x^i` with Horner's rule, to check transforms against in tests
+#[cfg(test)]
+pub(crate) fn evaluate_coeffs(coeffs: &[Fr], x: Fr) -> Fr {
+    coeffs
+        .iter()
+        .rev()
+        .fold(Fr::zero(), |acc, coeff| acc * x + coeff)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -68,18 +135,39 @@ mod tests {
 
     #[test]
     fn test_errors() {
-        let polynomial_empty = Polynomial::new(&vec![], 2);
-        assert_eq!(
-            polynomial_empty,
-            Err(PolynomialError::GenericError(
-                "elements are empty".to_string()
-            ))
-        );
+        let polynomial_empty = Polynomial::new(&[], 2);
+        assert_eq!(polynomial_empty, Err(PolynomialError::EmptyElements));
 
-        let polynomial_non_empty = Polynomial::new(&vec![Fr::one()], 2);
+        let polynomial_non_empty = Polynomial::new(&[Fr::one()], 2);
         assert_eq!(polynomial_non_empty.unwrap().is_empty(), false);
     }
 
+    #[test]
+    fn test_forms() {
+        use ark_std::UniformRand;
+
+        let rng = &mut rand::thread_rng();
+        let elements: Vec<Fr> = (0..5).map(|_| Fr::rand(rng)).collect();
+        let coeffs = Polynomial::new_with_form(&elements, 160, PolynomialForm::Coeff).unwrap();
+        assert_eq!(coeffs.form(), PolynomialForm::Coeff);
+        assert_eq!(coeffs.len(), 8);
+        assert_eq!(coeffs.to_coeff_form().unwrap(), coeffs);
+
+        let evals = coeffs.to_eval_form().unwrap();
+        assert_eq!(evals.form(), PolynomialForm::Eval);
+        assert_eq!(evals.get_length_of_padded_blob_as_fr_vector(), 5);
+        let domain = Domain::get(8).unwrap();
+        for i in 0..8 {
+            let expected = evaluate_coeffs(&elements, domain.element(i));
+            assert_eq!(evals.get_at_index(i), Some(&expected));
+        }
+        assert_eq!(evals.to_coeff_form().unwrap(), coeffs);
+        assert_eq!(
+            Polynomial::new(&elements, 160).unwrap().form(),
+            PolynomialForm::Eval
+        );
+    }
+
     #[test]
     fn test_to_fr_array() {
         use crate::{blob::Blob, consts::GETTYSBURG_ADDRESS_BYTES};
@@ -0,0 +1,281 @@
+//! Bulk decoding of the compressed big-endian point files that make up the SRS.
+//!
+//! Decompressing a point costs a square root, which makes loading millions of
+//! points slow. The decoders here split the input into chunks and, with the
+//! `parallel` feature enabled, decompress the chunks across threads.
+
+use crate::errors::KzgError;
+use crate::traits::PointCodec;
+use ark_bn254::{G1Affine, G2Affine};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::io::Read;
+#[cfg(feature = "parallel")]
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Number of points decoded by a single task before progress is reported.
+const POINTS_PER_CHUNK: usize = 1 << 12;
+
+/// Number of points pulled from a reader at once, keeps memory bounded on huge files.
+const POINTS_PER_READ: usize = 1 << 20;
+
+/// Called with the total number of points decoded so far.
+pub type ProgressCallback<'a> = &'a (dyn Fn(usize) + Sync);
+
+/// How much checking is done on every decoded point.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PointValidation {
+    /// Skip the subgroup check. Only for files from a trusted source, such as the
+    /// EigenDA SRS with a known checksum.
+    Trusted,
+    /// Run every check on every point.
+    Full,
+}
+
+/// Decodes a buffer of concatenated compressed g1 points.
+pub fn read_g1_points_from_bytes(
+    bytes: &[u8],
+    validation: PointValidation,
+    progress: Option<ProgressCallback>,
+) -> Result<Vec<G1Affine>, KzgError> {
+    decode_points(bytes, validation, 0, progress)
+}
+
+/// Decodes a buffer of concatenated compressed g2 points.
+pub fn read_g2_points_from_bytes(
+    bytes: &[u8],
+    validation: PointValidation,
+    progress: Option<ProgressCallback>,
+) -> Result<Vec<G2Affine>, KzgError> {
+    decode_points(bytes, validation, 0, progress)
+}
+
+/// Streams compressed g1 points out of a reader, stopping after `points_to_load`
+/// points or at the end of the reader when no limit is given.
+pub fn read_g1_points<R: Read>(
+    reader: R,
+    points_to_load: Option<usize>,
+    validation: PointValidation,
+    progress: Option<ProgressCallback>,
+) -> Result<Vec<G1Affine>, KzgError> {
+    read_points(reader, points_to_load, validation, progress)
+}
+
+/// Streams compressed g2 points out of a reader, see `read_g1_points`.
+pub fn read_g2_points<R: Read>(
+    reader: R,
+    points_to_load: Option<usize>,
+    validation: PointValidation,
+    progress: Option<ProgressCallback>,
+) -> Result<Vec<G2Affine>, KzgError> {
+    read_points(reader, points_to_load, validation, progress)
+}
+
+pub(crate) fn read_points<T: PointCodec, R: Read>(
+    mut reader: R,
+    points_to_load: Option<usize>,
+    validation: PointValidation,
+    progress: Option<ProgressCallback>,
+) -> Result<Vec<T>, KzgError> {
+    // the limit may come from an untrusted header, so it doesn't size the allocation
+    let mut points = Vec::with_capacity(points_to_load.unwrap_or_default().min(POINTS_PER_READ));
+    let mut buffer = vec![];
+
+    loop {
+        let batch = match points_to_load {
+            Some(limit) => POINTS_PER_READ.min(limit - points.len()),
+            None => POINTS_PER_READ,
+        };
+        if batch == 0 {
+            break;
+        }
+
+        buffer.resize(batch * T::COMPRESSED_SIZE, 0);
+        let read = read_full(&mut reader, &mut buffer)?;
+        if read % T::COMPRESSED_SIZE != 0 {
+            return Err(KzgError::TruncatedInput("point"));
+        }
+
+        let decoded = decode_points::<T>(&buffer[..read], validation, points.len(), progress)?;
+        points.extend(decoded);
+        if read < buffer.len() {
+            break;
+        }
+    }
+
+    if let Some(limit) = points_to_load {
+        if points.len() < limit {
+            return Err(KzgError::NotEnoughPoints {
+                expected: limit,
+                found: points.len(),
+            });
+        }
+    }
+    Ok(points)
+}
+
+/// fills the buffer unless the end of the reader is hit first, returns the number of bytes read.
+fn read_full<R: Read>(reader: &mut R, buffer: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..])? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    Ok(filled)
+}
+
+/// decodes a single chunk, `first_index` is only used to point at the failing point.
+fn decode_chunk<T: PointCodec>(
+    chunk: &[u8],
+    first_index: usize,
+    validation: PointValidation,
+) -> Result<Vec<T>, KzgError> {
+    chunk
+        .chunks(T::COMPRESSED_SIZE)
+        .enumerate()
+        .map(|(i, bytes)| {
+            match validation {
+                PointValidation::Trusted => T::from_compressed_be_unchecked(bytes),
+                PointValidation::Full => T::from_compressed_be(bytes),
+            }
+            .map_err(|source| KzgError::InvalidPoint {
+                index: first_index + i,
+                source,
+            })
+        })
+        .collect()
+}
+
+#[cfg(feature = "parallel")]
+fn decode_points<T: PointCodec>(
+    bytes: &[u8],
+    validation: PointValidation,
+    already_decoded: usize,
+    progress: Option<ProgressCallback>,
+) -> Result<Vec<T>, KzgError> {
+    if bytes.len() % T::COMPRESSED_SIZE != 0 {
+        return Err(KzgError::TruncatedInput("point"));
+    }
+
+    let decoded = AtomicUsize::new(already_decoded);
+    let chunks = bytes
+        .par_chunks(POINTS_PER_CHUNK * T::COMPRESSED_SIZE)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let points =
+                decode_chunk::<T>(chunk, already_decoded + i * POINTS_PER_CHUNK, validation)?;
+            let total = decoded.fetch_add(points.len(), Ordering::Relaxed) + points.len();
+            if let Some(progress) = progress {
+                progress(total);
+            }
+            Ok(points)
+        })
+        .collect::<Result<Vec<Vec<T>>, KzgError>>()?;
+    Ok(chunks.into_iter().flatten().collect())
+}
+
+#[cfg(not(feature = "parallel"))]
+fn decode_points<T: PointCodec>(
+    bytes: &[u8],
+    validation: PointValidation,
+    already_decoded: usize,
+    progress: Option<ProgressCallback>,
+) -> Result<Vec<T>, KzgError> {
+    if bytes.len() % T::COMPRESSED_SIZE != 0 {
+        return Err(KzgError::TruncatedInput("point"));
+    }
+
+    let mut points = Vec::with_capacity(bytes.len() / T::COMPRESSED_SIZE);
+    for chunk in bytes.chunks(POINTS_PER_CHUNK * T::COMPRESSED_SIZE) {
+        points.extend(decode_chunk::<T>(
+            chunk,
+            already_decoded + points.len(),
+            validation,
+        )?);
+        if let Some(progress) = progress {
+            progress(already_decoded + points.len());
+        }
+    }
+    Ok(points)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::PointDecodingError;
+    use crate::kzg::Kzg;
+    use lazy_static::lazy_static;
+    use std::fs;
+    use std::sync::Mutex;
+
+    lazy_static! {
+        static ref KZG_3000: Kzg = Kzg::setup(true).unwrap();
+    }
+
+    #[test]
+    fn test_read_points_from_bytes() {
+        let g1_bytes = fs::read("src/test-files/g1.point").unwrap();
+        let g2_bytes = fs::read("src/test-files/g2.point").unwrap();
+
+        for validation in [PointValidation::Trusted, PointValidation::Full] {
+            let g1 = read_g1_points_from_bytes(&g1_bytes, validation, None).unwrap();
+            assert_eq!(g1, KZG_3000.g1);
+            let g2 = read_g2_points_from_bytes(&g2_bytes[..64 * 100], validation, None).unwrap();
+            assert_eq!(g2, KZG_3000.g2[..100].to_vec());
+        }
+    }
+
+    #[test]
+    fn test_read_points_from_reader() {
+        let file = fs::File::open("src/test-files/g1.point").unwrap();
+        let g1 = read_g1_points(file, Some(1000), PointValidation::Trusted, None).unwrap();
+        assert_eq!(g1, KZG_3000.g1[..1000].to_vec());
+
+        let file = fs::File::open("src/test-files/g1.point").unwrap();
+        let g1 = read_g1_points(file, None, PointValidation::Trusted, None).unwrap();
+        assert_eq!(g1.len(), 3000);
+
+        let file = fs::File::open("src/test-files/g1.point").unwrap();
+        let result = read_g1_points(file, Some(3001), PointValidation::Trusted, None);
+        assert_eq!(
+            result,
+            Err(KzgError::NotEnoughPoints {
+                expected: 3001,
+                found: 3000
+            })
+        );
+    }
+
+    #[test]
+    fn test_read_points_progress() {
+        let g1_bytes = fs::read("src/test-files/g1.point").unwrap();
+        let reported = Mutex::new(vec![]);
+        let progress = |decoded: usize| reported.lock().unwrap().push(decoded);
+
+        read_g1_points_from_bytes(&g1_bytes, PointValidation::Trusted, Some(&progress)).unwrap();
+        let reported = reported.into_inner().unwrap();
+        assert!(!reported.is_empty());
+        assert_eq!(reported.iter().max(), Some(&3000));
+    }
+
+    #[test]
+    fn test_read_points_errors() {
+        let g1_bytes = fs::read("src/test-files/g1.point").unwrap();
+        let result = read_g1_points_from_bytes(&g1_bytes[..100], PointValidation::Full, None);
+        assert_eq!(result, Err(KzgError::TruncatedInput("point")));
+
+        let mut bad_infinity = g1_bytes[..32 * 10].to_vec();
+        bad_infinity[32 * 7] = 0b01 << 6;
+        bad_infinity[32 * 7 + 31] = 1;
+        let result = read_g1_points_from_bytes(&bad_infinity, PointValidation::Trusted, None);
+        assert_eq!(
+            result,
+            Err(KzgError::InvalidPoint {
+                index: 7,
+                source: PointDecodingError::InvalidInfinityEncoding
+            })
+        );
+    }
+}
@@ -1,24 +1,255 @@
+use crate::consts::{
+    SIZE_OF_G1_AFFINE_COMPRESSED, SIZE_OF_G1_AFFINE_UNCOMPRESSED, SIZE_OF_G2_AFFINE_COMPRESSED,
+    SIZE_OF_G2_AFFINE_UNCOMPRESSED,
+};
+use crate::errors::PointDecodingError;
 use crate::helpers;
 use ark_bn254::{g1::G1Affine, g2::G2Affine};
 use ark_ec::AffineRepr;
-use std::io;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
 
-#[allow(dead_code)]
-pub trait ReadPointFromBytes: AffineRepr {
-    fn read_point_from_bytes_be(bytes: &[u8]) -> io::Result<Self>;
+/// Encodes and decodes G1 and G2 points in every format the crate deals with:
+/// gnark's big-endian compressed and uncompressed formats, which is what EigenDA
+/// uses on the wire and in the SRS files, and the little-endian arkworks formats.
+///
+/// Every decoder fully validates the point unless it says otherwise, and the
+/// point at infinity round trips through every format.
+pub trait PointCodec: AffineRepr + CanonicalSerialize + CanonicalDeserialize {
+    /// size of the big-endian compressed encoding in bytes
+    const COMPRESSED_SIZE: usize;
+    /// size of the big-endian uncompressed encoding in bytes
+    const UNCOMPRESSED_SIZE: usize;
+
+    fn from_compressed_be(bytes: &[u8]) -> Result<Self, PointDecodingError>;
+
+    /// same as `from_compressed_be` without the subgroup check, only for points
+    /// from a trusted source
+    fn from_compressed_be_unchecked(bytes: &[u8]) -> Result<Self, PointDecodingError>;
+
+    fn to_compressed_be(&self) -> Vec<u8>;
+
+    fn from_uncompressed_be(bytes: &[u8]) -> Result<Self, PointDecodingError>;
+
+    fn to_uncompressed_be(&self) -> Vec<u8>;
+
+    /// checks the point is on the curve and in the prime order subgroup
+    fn validate(&self) -> Result<(), PointDecodingError>;
+
+    fn from_compressed_le(bytes: &[u8]) -> Result<Self, PointDecodingError> {
+        read_arkworks(bytes, Compress::Yes)
+    }
+
+    fn to_compressed_le(&self) -> Vec<u8> {
+        write_arkworks(self, Compress::Yes)
+    }
+
+    fn from_uncompressed_le(bytes: &[u8]) -> Result<Self, PointDecodingError> {
+        read_arkworks(bytes, Compress::No)
+    }
+
+    fn to_uncompressed_le(&self) -> Vec<u8> {
+        write_arkworks(self, Compress::No)
+    }
+}
+
+fn read_arkworks<P: PointCodec>(bytes: &[u8], compress: Compress) -> Result<P, PointDecodingError> {
+    let expected = P::zero().serialized_size(compress);
+    if bytes.len() != expected {
+        return Err(PointDecodingError::InvalidLength {
+            expected,
+            actual: bytes.len(),
+        });
+    }
+    // arkworks only reports a generic error when validation fails, so validate
+    // separately to surface the precise reason
+    let point = P::deserialize_with_mode(bytes, compress, Validate::No)
+        .map_err(|e| PointDecodingError::InvalidArkworksEncoding(e.to_string()))?;
+    point.validate()?;
+    Ok(point)
+}
+
+fn write_arkworks<P: PointCodec>(point: &P, compress: Compress) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(point.serialized_size(compress));
+    point
+        .serialize_with_mode(&mut bytes, compress)
+        .expect("serializing into a vector cannot fail");
+    bytes
+}
+
+impl PointCodec for G1Affine {
+    const COMPRESSED_SIZE: usize = SIZE_OF_G1_AFFINE_COMPRESSED;
+    const UNCOMPRESSED_SIZE: usize = SIZE_OF_G1_AFFINE_UNCOMPRESSED;
+
+    fn from_compressed_be(bytes: &[u8]) -> Result<Self, PointDecodingError> {
+        helpers::read_g1_point_from_bytes_be(bytes)
+    }
+
+    fn from_compressed_be_unchecked(bytes: &[u8]) -> Result<Self, PointDecodingError> {
+        helpers::read_g1_point_from_bytes_be_unchecked(bytes)
+    }
+
+    fn to_compressed_be(&self) -> Vec<u8> {
+        helpers::write_g1_point_to_bytes_be(self).to_vec()
+    }
+
+    fn from_uncompressed_be(bytes: &[u8]) -> Result<Self, PointDecodingError> {
+        helpers::read_g1_point_uncompressed_be(bytes)
+    }
+
+    fn to_uncompressed_be(&self) -> Vec<u8> {
+        helpers::write_g1_point_uncompressed_be(self).to_vec()
+    }
+
+    fn validate(&self) -> Result<(), PointDecodingError> {
+        helpers::validate_g1_point(self)
+    }
 }
 
-// Implement this trait for G1Affine and G2Affine
-impl ReadPointFromBytes for G1Affine {
-    fn read_point_from_bytes_be(bytes: &[u8]) -> io::Result<G1Affine> {
-        helpers::read_g1_point_from_bytes_be(&bytes.to_vec())
-            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+impl PointCodec for G2Affine {
+    const COMPRESSED_SIZE: usize = SIZE_OF_G2_AFFINE_COMPRESSED;
+    const UNCOMPRESSED_SIZE: usize = SIZE_OF_G2_AFFINE_UNCOMPRESSED;
+
+    fn from_compressed_be(bytes: &[u8]) -> Result<Self, PointDecodingError> {
+        helpers::read_g2_point_from_bytes_be(bytes)
+    }
+
+    fn from_compressed_be_unchecked(bytes: &[u8]) -> Result<Self, PointDecodingError> {
+        helpers::read_g2_point_from_bytes_be_unchecked(bytes)
+    }
+
+    fn to_compressed_be(&self) -> Vec<u8> {
+        helpers::write_g2_point_to_bytes_be(self).to_vec()
+    }
+
+    fn from_uncompressed_be(bytes: &[u8]) -> Result<Self, PointDecodingError> {
+        helpers::read_g2_point_uncompressed_be(bytes)
+    }
+
+    fn to_uncompressed_be(&self) -> Vec<u8> {
+        helpers::write_g2_point_uncompressed_be(self).to_vec()
+    }
+
+    fn validate(&self) -> Result<(), PointDecodingError> {
+        helpers::validate_g2_point(self)
     }
 }
 
-impl ReadPointFromBytes for G2Affine {
-    fn read_point_from_bytes_be(bytes: &[u8]) -> io::Result<G2Affine> {
-        helpers::read_g2_point_from_bytes_be(&bytes.to_vec())
-            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::{Fq, Fq2};
+    use ark_ff::{One, UniformRand};
+    use rand::thread_rng;
+
+    fn assert_round_trips<P: PointCodec>(point: P) {
+        let compressed = point.to_compressed_be();
+        assert_eq!(compressed.len(), P::COMPRESSED_SIZE);
+        assert_eq!(P::from_compressed_be(&compressed), Ok(point));
+        assert_eq!(P::from_compressed_be_unchecked(&compressed), Ok(point));
+
+        let uncompressed = point.to_uncompressed_be();
+        assert_eq!(uncompressed.len(), P::UNCOMPRESSED_SIZE);
+        assert_eq!(P::from_uncompressed_be(&uncompressed), Ok(point));
+
+        let mut arkworks = vec![];
+        point.serialize_compressed(&mut arkworks).unwrap();
+        assert_eq!(point.to_compressed_le(), arkworks);
+        assert_eq!(P::from_compressed_le(&arkworks), Ok(point));
+
+        arkworks.clear();
+        point.serialize_uncompressed(&mut arkworks).unwrap();
+        assert_eq!(point.to_uncompressed_le(), arkworks);
+        assert_eq!(P::from_uncompressed_le(&arkworks), Ok(point));
+    }
+
+    #[test]
+    fn test_point_codec_round_trip() {
+        let rng = &mut thread_rng();
+        for _ in 0..100 {
+            assert_round_trips(G1Affine::rand(rng));
+            assert_round_trips(G2Affine::rand(rng));
+        }
+        assert_round_trips(G1Affine::zero());
+        assert_round_trips(G2Affine::zero());
+        assert_round_trips(G1Affine::generator());
+        assert_round_trips(G2Affine::generator());
+    }
+
+    #[test]
+    fn test_uncompressed_be_layout() {
+        let point = G1Affine::generator();
+        let bytes = point.to_uncompressed_be();
+        assert_eq!(bytes[31], 1, "x of the generator is 1");
+        assert_eq!(bytes[63], 2, "y of the generator is 2");
+        assert_eq!(G1Affine::zero().to_uncompressed_be(), [0; 64]);
+        assert_eq!(G2Affine::zero().to_uncompressed_be(), [0; 128]);
+    }
+
+    #[test]
+    fn test_gnark_uncompressed_infinity() {
+        // gnark's `RawBytes()` of the point at infinity is all zeros
+        assert_eq!(
+            G1Affine::from_uncompressed_be(&[0; 64]),
+            Ok(G1Affine::zero())
+        );
+        assert_eq!(
+            G2Affine::from_uncompressed_be(&[0; 128]),
+            Ok(G2Affine::zero())
+        );
+    }
+
+    #[test]
+    fn test_point_codec_errors() {
+        assert_eq!(
+            G1Affine::from_uncompressed_be(&[0; 63]),
+            Err(PointDecodingError::InvalidLength {
+                expected: 64,
+                actual: 63
+            })
+        );
+        assert_eq!(
+            G2Affine::from_compressed_le(&[0; 63]),
+            Err(PointDecodingError::InvalidLength {
+                expected: 64,
+                actual: 63
+            })
+        );
+
+        let mut off_curve = G1Affine::generator();
+        off_curve.y += Fq::one();
+        assert_eq!(
+            G1Affine::from_uncompressed_be(&off_curve.to_uncompressed_be()),
+            Err(PointDecodingError::NotOnCurve)
+        );
+        assert_eq!(
+            G1Affine::from_uncompressed_le(&off_curve.to_uncompressed_le()),
+            Err(PointDecodingError::NotOnCurve)
+        );
+
+        let mut off_curve = G2Affine::generator();
+        off_curve.x += Fq2::one();
+        assert_eq!(
+            G2Affine::from_uncompressed_be(&off_curve.to_uncompressed_be()),
+            Err(PointDecodingError::NotOnCurve)
+        );
+
+        let mut flagged = G1Affine::generator().to_uncompressed_be();
+        flagged[0] |= 0b10 << 6;
+        assert_eq!(
+            G1Affine::from_uncompressed_be(&flagged),
+            Err(PointDecodingError::InvalidCompressionFlag(0b10 << 6))
+        );
+
+        // gnark reads this flag as a compressed infinity
+        let mut compressed_infinity = [0u8; 128];
+        compressed_infinity[0] = 0b01 << 6;
+        assert_eq!(
+            G2Affine::from_uncompressed_be(&compressed_infinity),
+            Err(PointDecodingError::InvalidCompressionFlag(0b01 << 6))
+        );
+        assert_eq!(
+            G1Affine::from_uncompressed_be(&compressed_infinity[..64]),
+            Err(PointDecodingError::InvalidCompressionFlag(0b01 << 6))
+        );
     }
 }